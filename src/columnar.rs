@@ -0,0 +1,179 @@
+use arrow::array::{
+    ArrayRef, BinaryBuilder, BooleanBuilder, Date32Builder, Float64Builder, Int64Builder,
+    StringBuilder, Time64MicrosecondBuilder, TimestampMicrosecondBuilder, UInt64Builder,
+};
+use arrow::datatypes::{DataType, Field, Schema, TimeUnit};
+use arrow::record_batch::RecordBatch;
+use sqlx::types::chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+use std::sync::Arc;
+
+use crate::MySQLDataTypes;
+use crate::PostgreSQLDataTypes;
+use crate::Result;
+use crate::RssqlError;
+use crate::SQLDataTypes;
+use crate::SQLRets;
+use crate::SQLiteDataTypes;
+
+/// The value of the column converted for arrow, the others are written as the display string.
+enum Scalar<'a> {
+    Null,
+    Bool(bool),
+    Int(i64),
+    UInt(u64),
+    Float(f64),
+    Binary(&'a [u8]),
+    Date(NaiveDate),
+    NaiveDateTime(NaiveDateTime),
+    DateTime(NaiveDateTime),
+    Time(NaiveTime),
+    String(String),
+}
+
+/// Convert the value of the backend, the variants shared by all backends are listed here
+/// and the others of the backend are given as the extra arms.
+macro_rules! scalar {
+    ($value:expr, $t:ident, { $($extra:tt)* }) => {
+        match $value {
+            $t::Null => Scalar::Null,
+            $t::Bool(v) => Scalar::Bool(*v),
+            $t::I8(v) => Scalar::Int(i64::from(*v)),
+            $t::I16(v) => Scalar::Int(i64::from(*v)),
+            $t::I32(v) => Scalar::Int(i64::from(*v)),
+            $t::I64(v) => Scalar::Int(*v),
+            $t::U64(v) => Scalar::UInt(*v),
+            $t::F64(v) => Scalar::Float(*v),
+            $t::Binary(v) => Scalar::Binary(v),
+            $t::NaiveDate(v) => Scalar::Date(*v),
+            $t::NaiveDateTime(v) => Scalar::NaiveDateTime(*v),
+            $t::DateTime(v) => Scalar::DateTime(v.naive_utc()),
+            $t::NaiveTime(v) => Scalar::Time(*v),
+            $($extra)*
+            other => Scalar::String(other.to_string()),
+        }
+    };
+}
+
+fn to_scalar(value: &SQLDataTypes) -> Scalar<'_> {
+    match value {
+        SQLDataTypes::MySQLDataTypes(m) => scalar!(m, MySQLDataTypes, {
+            MySQLDataTypes::U8(v) => Scalar::Int(i64::from(*v)),
+            MySQLDataTypes::U16(v) | MySQLDataTypes::Year(v) => Scalar::Int(i64::from(*v)),
+            MySQLDataTypes::U32(v) => Scalar::Int(i64::from(*v)),
+            MySQLDataTypes::F32(v) => Scalar::Float(f64::from(*v)),
+        }),
+        SQLDataTypes::PostgreSQLDataTypes(p) => scalar!(p, PostgreSQLDataTypes, {
+            PostgreSQLDataTypes::U8(v) => Scalar::Int(i64::from(*v)),
+            PostgreSQLDataTypes::U16(v) => Scalar::Int(i64::from(*v)),
+            PostgreSQLDataTypes::F32(v) => Scalar::Float(f64::from(*v)),
+        }),
+        SQLDataTypes::SQLiteDataTypes(s) => scalar!(s, SQLiteDataTypes, {
+            SQLiteDataTypes::U32(v) => Scalar::Int(i64::from(*v)),
+        }),
+    }
+}
+
+/// The arrow type of the scalar, `None` for the null value.
+fn data_type(scalar: &Scalar) -> Option<DataType> {
+    let data_type = match scalar {
+        Scalar::Null => return None,
+        Scalar::Bool(_) => DataType::Boolean,
+        Scalar::Int(_) => DataType::Int64,
+        Scalar::UInt(_) => DataType::UInt64,
+        Scalar::Float(_) => DataType::Float64,
+        Scalar::Binary(_) => DataType::Binary,
+        Scalar::Date(_) => DataType::Date32,
+        Scalar::NaiveDateTime(_) => DataType::Timestamp(TimeUnit::Microsecond, None),
+        Scalar::DateTime(_) => DataType::Timestamp(TimeUnit::Microsecond, Some("UTC".into())),
+        Scalar::Time(_) => DataType::Time64(TimeUnit::Microsecond),
+        Scalar::String(_) => DataType::Utf8,
+    };
+    Some(data_type)
+}
+
+/// Build the arrow array of the column from the values, the missing and null values are arrow nulls.
+fn build_array(column_name: &str, data_type: &DataType, scalars: Vec<Scalar>) -> Result<ArrayRef> {
+    let mismatch = || {
+        RssqlError::InvalidValue(format!(
+            "the column {} has the values of different types",
+            column_name
+        ))
+    };
+    macro_rules! build {
+        ($builder:expr, $($pattern:pat => $v:expr),+) => {{
+            let mut builder = $builder;
+            for scalar in scalars {
+                match scalar {
+                    Scalar::Null => builder.append_null(),
+                    $($pattern => builder.append_value($v),)+
+                    _ => return Err(mismatch()),
+                }
+            }
+            Arc::new(builder.finish()) as ArrayRef
+        }};
+    }
+    let epoch = NaiveDate::from_ymd_opt(1970, 1, 1).unwrap_or_default();
+    let array = match data_type {
+        DataType::Boolean => build!(BooleanBuilder::new(), Scalar::Bool(v) => v),
+        DataType::Int64 => build!(Int64Builder::new(), Scalar::Int(v) => v),
+        DataType::UInt64 => build!(UInt64Builder::new(), Scalar::UInt(v) => v),
+        DataType::Float64 => build!(Float64Builder::new(), Scalar::Float(v) => v),
+        DataType::Binary => build!(BinaryBuilder::new(), Scalar::Binary(v) => v),
+        DataType::Date32 => build!(
+            Date32Builder::new(),
+            Scalar::Date(v) => i32::try_from((v - epoch).num_days()).map_err(|_| mismatch())?
+        ),
+        DataType::Timestamp(_, None) => build!(
+            TimestampMicrosecondBuilder::new(),
+            Scalar::NaiveDateTime(v) => v.and_utc().timestamp_micros()
+        ),
+        DataType::Timestamp(_, Some(_)) => build!(
+            TimestampMicrosecondBuilder::new().with_timezone("UTC"),
+            Scalar::DateTime(v) => v.and_utc().timestamp_micros()
+        ),
+        DataType::Time64(_) => build!(
+            Time64MicrosecondBuilder::new(),
+            Scalar::Time(v) => (v - NaiveTime::MIN).num_microseconds().ok_or_else(mismatch)?
+        ),
+        _ => {
+            let mut builder = StringBuilder::new();
+            for scalar in scalars {
+                match scalar {
+                    Scalar::Null => builder.append_null(),
+                    Scalar::String(v) => builder.append_value(v),
+                    _ => return Err(mismatch()),
+                }
+            }
+            Arc::new(builder.finish()) as ArrayRef
+        }
+    };
+    Ok(array)
+}
+
+/// Convert the returns to the record batch, see `TryFrom<&SQLRets> for RecordBatch`.
+pub(crate) fn to_record_batch(rets: &SQLRets) -> Result<RecordBatch> {
+    let mut fields = Vec::with_capacity(rets.column.len());
+    let mut arrays = Vec::with_capacity(rets.column.len());
+    for name in &rets.column {
+        let scalars: Vec<Scalar> = rets
+            .iter()
+            .map(|row| row.get(name).map_or(Scalar::Null, to_scalar))
+            .collect();
+        let data_type = scalars.iter().find_map(data_type).unwrap_or(DataType::Utf8);
+        arrays.push(build_array(name, &data_type, scalars)?);
+        fields.push(Field::new(name, data_type, true));
+    }
+    let schema = Arc::new(Schema::new(fields));
+    Ok(RecordBatch::try_new(schema, arrays)?)
+}
+
+/// Write the returns as the parquet file, see `to_record_batch`.
+#[cfg(feature = "parquet")]
+pub(crate) fn to_parquet_bytes(rets: &SQLRets) -> Result<Vec<u8>> {
+    let batch = to_record_batch(rets)?;
+    let mut bytes = Vec::new();
+    let mut writer = parquet::arrow::ArrowWriter::try_new(&mut bytes, batch.schema(), None)?;
+    writer.write(&batch)?;
+    writer.close()?;
+    Ok(bytes)
+}
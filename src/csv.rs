@@ -0,0 +1,97 @@
+use crate::Result;
+use crate::RssqlError;
+
+/// One field of the csv record.
+pub struct Field {
+    pub value: String,
+    /// The field is written inside double quotes.
+    pub quoted: bool,
+}
+
+/// Quote the value and escape the double quotes inside it.
+pub fn quote(value: &str) -> String {
+    format!("\"{}\"", value.replace('"', "\"\""))
+}
+
+/// Parse the csv text to records, the quoted fields can contain `,`, `""` and line breaks.
+pub fn parse(csv: &str) -> Result<Vec<Vec<Field>>> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = Field {
+        value: String::new(),
+        quoted: false,
+    };
+    let mut in_quote = false;
+    let mut line = 1;
+    let mut chars = csv.chars().peekable();
+    while let Some(c) = chars.next() {
+        if in_quote {
+            match c {
+                '"' => {
+                    if chars.peek() == Some(&'"') {
+                        chars.next();
+                        field.value.push('"');
+                    } else {
+                        in_quote = false;
+                    }
+                }
+                '\n' => {
+                    line += 1;
+                    field.value.push(c);
+                }
+                _ => field.value.push(c),
+            }
+            continue;
+        }
+        match c {
+            '"' => {
+                if !field.value.is_empty() || field.quoted {
+                    return Err(RssqlError::ParseError(format!(
+                        "unexpected double quote in csv at line {}",
+                        line
+                    )));
+                }
+                in_quote = true;
+                field.quoted = true;
+            }
+            ',' => {
+                let value = std::mem::take(&mut field.value);
+                record.push(Field {
+                    value,
+                    quoted: field.quoted,
+                });
+                field.quoted = false;
+            }
+            '\r' if chars.peek() == Some(&'\n') => (),
+            '\n' => {
+                let value = std::mem::take(&mut field.value);
+                record.push(Field {
+                    value,
+                    quoted: field.quoted,
+                });
+                field.quoted = false;
+                records.push(std::mem::take(&mut record));
+                line += 1;
+            }
+            _ => {
+                if field.quoted {
+                    return Err(RssqlError::ParseError(format!(
+                        "unexpected char after the quoted field in csv at line {}",
+                        line
+                    )));
+                }
+                field.value.push(c)
+            }
+        }
+    }
+    if in_quote {
+        return Err(RssqlError::ParseError(
+            "unclosed double quote in csv".to_string(),
+        ));
+    }
+    if !field.value.is_empty() || field.quoted || !record.is_empty() {
+        record.push(field);
+        records.push(record);
+    }
+    Ok(records)
+}
@@ -0,0 +1,348 @@
+use std::fmt;
+
+use crate::Result;
+use crate::RssqlError;
+
+/// Percent-encode the chars which are not unreserved in url.
+fn encode(value: &str) -> String {
+    let mut encoded = String::new();
+    for b in value.bytes() {
+        match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                encoded.push(b as char)
+            }
+            _ => encoded.push_str(&format!("%{:02X}", b)),
+        }
+    }
+    encoded
+}
+
+fn decode(value: &str) -> Result<String> {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let hex = value.get(i + 1..i + 3).ok_or_else(|| {
+                RssqlError::ParseError(format!("invalid percent-encoding in {}", value))
+            })?;
+            decoded.push(u8::from_str_radix(hex, 16)?);
+            i += 3;
+        } else {
+            decoded.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8(decoded).map_err(|e| RssqlError::ParseError(format!("{} in {}", e, value)))
+}
+
+fn build_params(params: &[(String, String)]) -> String {
+    let params: Vec<String> = params
+        .iter()
+        .map(|(key, value)| format!("{}={}", encode(key), encode(value)))
+        .collect();
+    params.join("&")
+}
+
+fn parse_params(query: &str) -> Result<Vec<(String, String)>> {
+    let mut params = Vec::new();
+    for param in query.split('&').filter(|p| !p.is_empty()) {
+        let (key, value) = param.split_once('=').unwrap_or((param, ""));
+        params.push((decode(key)?, decode(value)?));
+    }
+    Ok(params)
+}
+
+/// The dsn of the database server, shared by mysql (mariadb) and postgresql.
+macro_rules! server_dsn {
+    ($name:ident, $db:expr, $scheme:expr, [$($accepted:expr),+]) => {
+        #[doc = concat!("The connection url builder of ", $db, ".")]
+        ///
+        /// It can also be parsed from the url by `TryFrom<&str>` to modify a base url.
+        /// The password is masked as `***` by `Debug` and `Display`, only `build` returns the real one.
+        #[derive(Clone, PartialEq, Eq)]
+        pub struct $name {
+            host: String,
+            port: Option<u16>,
+            user: Option<String>,
+            password: Option<String>,
+            database: Option<String>,
+            params: Vec<(String, String)>,
+        }
+
+        impl $name {
+            pub fn new(host: &str) -> $name {
+                $name {
+                    host: host.to_string(),
+                    port: None,
+                    user: None,
+                    password: None,
+                    database: None,
+                    params: Vec::new(),
+                }
+            }
+            pub fn host(mut self, host: &str) -> Self {
+                self.host = host.to_string();
+                self
+            }
+            pub fn port(mut self, port: u16) -> Self {
+                self.port = Some(port);
+                self
+            }
+            pub fn user(mut self, user: &str) -> Self {
+                self.user = Some(user.to_string());
+                self
+            }
+            pub fn password(mut self, password: &str) -> Self {
+                self.password = Some(password.to_string());
+                self
+            }
+            pub fn database(mut self, database: &str) -> Self {
+                self.database = Some(database.to_string());
+                self
+            }
+            /// Add the query string param, the param with the same key is replaced.
+            pub fn param(mut self, key: &str, value: &str) -> Self {
+                self.params.retain(|(k, _)| k != key);
+                self.params.push((key.to_string(), value.to_string()));
+                self
+            }
+            /// Build the url, the user, password, database and params are percent-encoded.
+            pub fn build(&self) -> String {
+                self.url(false)
+            }
+            fn url(&self, mask: bool) -> String {
+                let mut url = format!("{}://", $scheme);
+                if let Some(user) = &self.user {
+                    url.push_str(&encode(user));
+                    if let Some(password) = &self.password {
+                        url.push(':');
+                        match mask {
+                            true => url.push_str("***"),
+                            false => url.push_str(&encode(password)),
+                        }
+                    }
+                    url.push('@');
+                }
+                url.push_str(&self.host);
+                if let Some(port) = self.port {
+                    url.push_str(&format!(":{}", port));
+                }
+                if let Some(database) = &self.database {
+                    url.push('/');
+                    url.push_str(&encode(database));
+                }
+                if !self.params.is_empty() {
+                    url.push('?');
+                    url.push_str(&build_params(&self.params));
+                }
+                url
+            }
+        }
+
+        impl TryFrom<&str> for $name {
+            type Error = RssqlError;
+            fn try_from(url: &str) -> Result<Self> {
+                let (scheme, rest) = url
+                    .split_once("://")
+                    .ok_or_else(|| RssqlError::ParseError(format!("invalid {} url: {}", $db, url)))?;
+                if ![$($accepted),+].contains(&scheme.to_lowercase().as_str()) {
+                    return Err(RssqlError::ParseError(format!("invalid {} url scheme: {}", $db, scheme)));
+                }
+                let (rest, query) = rest.split_once('?').unwrap_or((rest, ""));
+                let (authority, database) = match rest.split_once('/') {
+                    Some((authority, database)) if !database.is_empty() => {
+                        (authority, Some(decode(database)?))
+                    }
+                    Some((authority, _)) => (authority, None),
+                    None => (rest, None),
+                };
+                let (userinfo, hostport) = match authority.rsplit_once('@') {
+                    Some((userinfo, hostport)) => (Some(userinfo), hostport),
+                    None => (None, authority),
+                };
+                let (user, password) = match userinfo {
+                    Some(userinfo) => match userinfo.split_once(':') {
+                        Some((user, password)) => (Some(decode(user)?), Some(decode(password)?)),
+                        None => (Some(decode(userinfo)?), None),
+                    },
+                    None => (None, None),
+                };
+                // the ipv6 host is inside the brackets
+                let port_start = match hostport.rfind(']') {
+                    Some(i) => hostport[i..].find(':').map(|j| i + j),
+                    None => hostport.rfind(':'),
+                };
+                let (host, port) = match port_start {
+                    Some(i) => (&hostport[..i], Some(hostport[i + 1..].parse()?)),
+                    None => (hostport, None),
+                };
+                Ok($name {
+                    host: host.to_string(),
+                    port,
+                    user,
+                    password,
+                    database,
+                    params: parse_params(query)?,
+                })
+            }
+        }
+
+        impl From<$name> for String {
+            fn from(dsn: $name) -> String {
+                dsn.build()
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                write!(f, "{}", self.url(true))
+            }
+        }
+
+        impl fmt::Debug for $name {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.debug_struct(stringify!($name))
+                    .field("host", &self.host)
+                    .field("port", &self.port)
+                    .field("user", &self.user)
+                    .field("password", &self.password.as_ref().map(|_| "***"))
+                    .field("database", &self.database)
+                    .field("params", &self.params)
+                    .finish()
+            }
+        }
+    };
+}
+
+server_dsn!(MySQLDsn, "mysql (mariadb)", "mysql", ["mysql", "mariadb"]);
+server_dsn!(
+    PostgreSQLDsn,
+    "postgresql",
+    "postgres",
+    ["postgres", "postgresql", "postgre"]
+);
+
+/// The open mode of the sqlite database.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SQLiteMode {
+    /// `ro`
+    ReadOnly,
+    /// `rw`
+    ReadWrite,
+    /// `rwc`, create the database file if it does not exist.
+    ReadWriteCreate,
+    /// `memory`
+    Memory,
+}
+
+impl SQLiteMode {
+    fn as_str(&self) -> &'static str {
+        match self {
+            SQLiteMode::ReadOnly => "ro",
+            SQLiteMode::ReadWrite => "rw",
+            SQLiteMode::ReadWriteCreate => "rwc",
+            SQLiteMode::Memory => "memory",
+        }
+    }
+}
+
+impl TryFrom<&str> for SQLiteMode {
+    type Error = RssqlError;
+    fn try_from(mode: &str) -> Result<Self> {
+        match mode {
+            "ro" => Ok(SQLiteMode::ReadOnly),
+            "rw" => Ok(SQLiteMode::ReadWrite),
+            "rwc" => Ok(SQLiteMode::ReadWriteCreate),
+            "memory" => Ok(SQLiteMode::Memory),
+            _ => Err(RssqlError::ParseError(format!(
+                "invalid sqlite mode: {}",
+                mode
+            ))),
+        }
+    }
+}
+
+/// The connection url builder of sqlite.
+///
+/// It can also be parsed from the url by `TryFrom<&str>` to modify a base url.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SQLiteDsn {
+    file_path: String,
+    mode: Option<SQLiteMode>,
+    params: Vec<(String, String)>,
+}
+
+impl SQLiteDsn {
+    pub fn new(file_path: &str) -> SQLiteDsn {
+        SQLiteDsn {
+            file_path: file_path.to_string(),
+            mode: None,
+            params: Vec::new(),
+        }
+    }
+    pub fn file_path(mut self, file_path: &str) -> Self {
+        self.file_path = file_path.to_string();
+        self
+    }
+    pub fn mode(mut self, mode: SQLiteMode) -> Self {
+        self.mode = Some(mode);
+        self
+    }
+    /// Add the query string param, the param with the same key is replaced.
+    pub fn param(mut self, key: &str, value: &str) -> Self {
+        self.params.retain(|(k, _)| k != key);
+        self.params.push((key.to_string(), value.to_string()));
+        self
+    }
+    pub fn build(&self) -> String {
+        let mut params = Vec::new();
+        if let Some(mode) = self.mode {
+            params.push(("mode".to_string(), mode.as_str().to_string()));
+        }
+        params.extend(self.params.iter().cloned());
+        let mut url = format!("sqlite:{}", self.file_path);
+        if !params.is_empty() {
+            url.push('?');
+            url.push_str(&build_params(&params));
+        }
+        url
+    }
+}
+
+impl TryFrom<&str> for SQLiteDsn {
+    type Error = RssqlError;
+    fn try_from(url: &str) -> Result<Self> {
+        let rest = url
+            .strip_prefix("sqlite:")
+            .ok_or_else(|| RssqlError::ParseError(format!("invalid sqlite url: {}", url)))?;
+        let rest = rest.strip_prefix("//").unwrap_or(rest);
+        let (file_path, query) = rest.split_once('?').unwrap_or((rest, ""));
+        let mut mode = None;
+        let mut params = Vec::new();
+        for (key, value) in parse_params(query)? {
+            if key == "mode" {
+                mode = Some(SQLiteMode::try_from(value.as_str())?);
+            } else {
+                params.push((key, value));
+            }
+        }
+        Ok(SQLiteDsn {
+            file_path: file_path.to_string(),
+            mode,
+            params,
+        })
+    }
+}
+
+impl From<SQLiteDsn> for String {
+    fn from(dsn: SQLiteDsn) -> String {
+        dsn.build()
+    }
+}
+
+impl fmt::Display for SQLiteDsn {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.build())
+    }
+}
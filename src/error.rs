@@ -0,0 +1,295 @@
+use std::error::Error;
+use std::fmt;
+use std::io::ErrorKind;
+use std::time::Duration;
+
+use crate::CONNECTION_CLOSED_ERROR;
+
+/// The result of rssql, all the public methods return the `RssqlError` on failure.
+pub type Result<T, E = RssqlError> = std::result::Result<T, E>;
+
+/// The kinds of the errors raised by rssql.
+///
+/// ```
+/// use rssql::{RssqlError, SQLite};
+/// async fn test_error() {
+///     let mut sqlite = SQLite::connect("sqlite:sqlite_test.db?mode=rwc").await.unwrap();
+///     match sqlite.execute("SELECT * FROM info").await {
+///         Ok(rets) => println!("{}", rets),
+///         Err(RssqlError::QueryError { sql, source }) => println!("{} failed: {}", sql, source),
+///         Err(e) => println!("{}", e),
+///     }
+/// }
+/// ```
+///
+/// With the `anyhow` feature the `anyhow::Error` can be converted to `RssqlError::Anyhow`,
+/// such as the error returned by the closure of `execute_fetch_all_mapped`.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum RssqlError {
+    /// Failed to connect to the database or lost the connection, such as the connection timeout.
+    ConnectionError(sqlx::Error),
+    /// The database failed to run the sql, the `sql` is empty if it is unknown.
+    QueryError { sql: String, source: sqlx::Error },
+    /// The column does not exist in the returns.
+    ColumnNotFound(String),
+    /// The column already exists in the returns.
+    ColumnExists(String),
+    /// The table does not exist in the database.
+    TableNotFound(String),
+    /// The value can not be converted, `from` is the sql type name of the value.
+    TypeConversionError { from: String, to: String },
+    /// The value is not valid for the operation, such as the non-numeric value to sum.
+    InvalidValue(String),
+    /// The argument is not valid, such as the invalid identifier or the out of range index.
+    InvalidArgument(String),
+    /// Failed to parse the text, such as the csv, the json or the url.
+    ParseError(String),
+    /// The connection is closed by `close`.
+    ConnectionClosed,
+    /// The number of the params does not match the placeholders of the sql.
+    ParameterCountMismatch { expected: usize, got: usize },
+    /// The query is canceled by the server because it runs longer than the timeout.
+    QueryTimeout { elapsed: Duration },
+    /// Failed to read or write the file.
+    Io(std::io::Error),
+    /// The error with the context of where it happened, such as the statement of the script.
+    Context {
+        context: String,
+        source: Box<RssqlError>,
+    },
+    #[cfg(feature = "arrow")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "arrow")))]
+    Arrow(arrow::error::ArrowError),
+    #[cfg(feature = "parquet")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "parquet")))]
+    Parquet(parquet::errors::ParquetError),
+    #[cfg(feature = "anyhow")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "anyhow")))]
+    Anyhow(anyhow::Error),
+}
+
+impl fmt::Display for RssqlError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RssqlError::ConnectionError(e) => write!(f, "{}", e),
+            RssqlError::QueryError { source, .. } => write!(f, "{}", source),
+            RssqlError::ColumnNotFound(name) => write!(f, "the column {} does not exist", name),
+            RssqlError::ColumnExists(name) => write!(f, "the column {} already exists", name),
+            RssqlError::TableNotFound(name) => write!(f, "the table {} does not exist", name),
+            RssqlError::TypeConversionError { from, to } => {
+                write!(f, "can not convert {} to {}", from, to)
+            }
+            RssqlError::InvalidValue(message)
+            | RssqlError::InvalidArgument(message)
+            | RssqlError::ParseError(message) => write!(f, "{}", message),
+            RssqlError::ConnectionClosed => write!(f, "{}", CONNECTION_CLOSED_ERROR),
+            RssqlError::ParameterCountMismatch { expected, got } => write!(
+                f,
+                "the sql has {} placeholders but {} params were given",
+                expected, got
+            ),
+            RssqlError::QueryTimeout { elapsed } => {
+                write!(f, "the query was canceled after {:?}", elapsed)
+            }
+            RssqlError::Io(e) => write!(f, "{}", e),
+            RssqlError::Context { context, .. } => write!(f, "{}", context),
+            #[cfg(feature = "arrow")]
+            RssqlError::Arrow(e) => write!(f, "{}", e),
+            #[cfg(feature = "parquet")]
+            RssqlError::Parquet(e) => write!(f, "{}", e),
+            #[cfg(feature = "anyhow")]
+            RssqlError::Anyhow(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl Error for RssqlError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            RssqlError::ConnectionError(source) | RssqlError::QueryError { source, .. } => {
+                Some(source)
+            }
+            RssqlError::Io(source) => Some(source),
+            RssqlError::Context { source, .. } => Some(source.as_ref()),
+            #[cfg(feature = "arrow")]
+            RssqlError::Arrow(source) => Some(source),
+            #[cfg(feature = "parquet")]
+            RssqlError::Parquet(source) => Some(source),
+            #[cfg(feature = "anyhow")]
+            RssqlError::Anyhow(source) => Some(source.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+impl RssqlError {
+    /// Returns the innermost error of the `Context` errors.
+    pub fn root(&self) -> &RssqlError {
+        match self {
+            RssqlError::Context { source, .. } => source.root(),
+            e => e,
+        }
+    }
+}
+
+impl From<sqlx::Error> for RssqlError {
+    fn from(e: sqlx::Error) -> Self {
+        match is_connection_error(&e) {
+            true => RssqlError::ConnectionError(e),
+            false => RssqlError::QueryError {
+                sql: String::new(),
+                source: e,
+            },
+        }
+    }
+}
+
+impl From<std::io::Error> for RssqlError {
+    fn from(e: std::io::Error) -> Self {
+        RssqlError::Io(e)
+    }
+}
+
+/// The errors of parsing the text are converted to `RssqlError::ParseError`.
+macro_rules! impl_from_parse_error {
+    ($($t:ty),*) => {
+        $(impl From<$t> for RssqlError {
+            fn from(e: $t) -> Self {
+                RssqlError::ParseError(e.to_string())
+            }
+        })*
+    };
+}
+
+impl_from_parse_error!(
+    std::num::ParseIntError,
+    std::num::ParseFloatError,
+    std::str::ParseBoolError,
+    chrono::ParseError
+);
+
+impl From<std::num::TryFromIntError> for RssqlError {
+    fn from(e: std::num::TryFromIntError) -> Self {
+        RssqlError::InvalidValue(e.to_string())
+    }
+}
+
+#[cfg(feature = "arrow")]
+impl From<arrow::error::ArrowError> for RssqlError {
+    fn from(e: arrow::error::ArrowError) -> Self {
+        RssqlError::Arrow(e)
+    }
+}
+
+#[cfg(feature = "parquet")]
+impl From<parquet::errors::ParquetError> for RssqlError {
+    fn from(e: parquet::errors::ParquetError) -> Self {
+        RssqlError::Parquet(e)
+    }
+}
+
+#[cfg(feature = "anyhow")]
+impl From<anyhow::Error> for RssqlError {
+    fn from(e: anyhow::Error) -> Self {
+        match e.downcast::<RssqlError>() {
+            Ok(e) => e,
+            Err(e) => RssqlError::Anyhow(e),
+        }
+    }
+}
+
+/// Attach the context to the error, like `anyhow::Context`.
+pub(crate) trait Context<T> {
+    fn with_context<F: FnOnce() -> String>(self, f: F) -> Result<T>;
+}
+
+impl<T, E: Into<RssqlError>> Context<T> for std::result::Result<T, E> {
+    fn with_context<F: FnOnce() -> String>(self, f: F) -> Result<T> {
+        self.map_err(|e| RssqlError::Context {
+            context: f(),
+            source: Box::new(e.into()),
+        })
+    }
+}
+
+fn is_connection_error(e: &sqlx::Error) -> bool {
+    matches!(
+        e,
+        sqlx::Error::Configuration(_)
+            | sqlx::Error::Io(_)
+            | sqlx::Error::Tls(_)
+            | sqlx::Error::PoolTimedOut
+            | sqlx::Error::PoolClosed
+    )
+}
+
+/// Attach the sql to the database error of the query, the other errors are returned as they are.
+pub(crate) fn with_sql(e: RssqlError, sql: &str) -> RssqlError {
+    match e {
+        RssqlError::QueryError { sql: old, source } if old.is_empty() => RssqlError::QueryError {
+            sql: sql.to_string(),
+            source,
+        },
+        e => e,
+    }
+}
+
+/// Whether the error may go away by retrying the sql on a new connection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ErrorClass {
+    /// The network errors, such as the connection reset, the broken pipe or the timeout.
+    Transient,
+    /// The others, such as the syntax errors or the constraint violations.
+    Permanent,
+}
+
+pub(crate) fn classify_error(e: &sqlx::Error) -> ErrorClass {
+    match e {
+        sqlx::Error::Io(e) => match e.kind() {
+            ErrorKind::ConnectionReset
+            | ErrorKind::ConnectionAborted
+            | ErrorKind::ConnectionRefused
+            | ErrorKind::NotConnected
+            | ErrorKind::BrokenPipe
+            | ErrorKind::TimedOut
+            | ErrorKind::UnexpectedEof
+            | ErrorKind::Interrupted => ErrorClass::Transient,
+            _ => ErrorClass::Permanent,
+        },
+        sqlx::Error::PoolTimedOut => ErrorClass::Transient,
+        _ => ErrorClass::Permanent,
+    }
+}
+
+/// Classify the error returned by rssql by the sqlx error inside.
+pub(crate) fn classify(e: &RssqlError) -> ErrorClass {
+    match e.root() {
+        RssqlError::ConnectionError(e) | RssqlError::QueryError { source: e, .. } => {
+            classify_error(e)
+        }
+        _ => ErrorClass::Permanent,
+    }
+}
+
+/// Returns `base_delay * 2^attempt`, at most 30 seconds.
+pub(crate) fn backoff_delay(base_delay: Duration, attempt: u32) -> Duration {
+    let max_delay = Duration::from_secs(30);
+    2u32.checked_pow(attempt)
+        .and_then(|factor| base_delay.checked_mul(factor))
+        .map_or(max_delay, |delay| delay.min(max_delay))
+}
+
+/// Check if the query is canceled by the statement timeout of the postgresql
+/// or the max execution time of the mysql (mariadb).
+pub(crate) fn is_query_timeout(e: &sqlx::Error) -> bool {
+    match e {
+        sqlx::Error::Database(e) => match e.try_downcast_ref::<sqlx::mysql::MySqlDatabaseError>() {
+            // ER_QUERY_TIMEOUT of mysql and ER_STATEMENT_TIMEOUT of mariadb.
+            Some(e) => matches!(e.number(), 3024 | 1969),
+            // query_canceled of postgresql.
+            None => e.code().as_deref() == Some("57014"),
+        },
+        _ => false,
+    }
+}
@@ -0,0 +1,53 @@
+/// Returns the keys of the first object inside the top-level json array, in the order they are written.
+///
+/// The json must be valid, such as the text already parsed by `serde_json`.
+pub fn first_object_keys(json: &str) -> Vec<String> {
+    let mut keys = Vec::new();
+    let mut depth = 0;
+    let mut string_start = None;
+    let mut last_string = None;
+    let mut escaped = false;
+    for (i, c) in json.char_indices() {
+        if let Some(start) = string_start {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => {
+                    string_start = None;
+                    last_string = Some(&json[start..=i]);
+                }
+                _ => (),
+            }
+            continue;
+        }
+        match c {
+            '"' => string_start = Some(i),
+            ':' if depth == 2 => {
+                if let Some(key) = last_string.and_then(unescape) {
+                    keys.push(key);
+                }
+            }
+            '[' | '{' => depth += 1,
+            ']' | '}' => {
+                depth -= 1;
+                // The end of the first object.
+                if depth == 1 {
+                    break;
+                }
+            }
+            _ => (),
+        }
+        if !c.is_whitespace() && c != '"' {
+            last_string = None;
+        }
+    }
+    keys
+}
+
+/// Unescape the quoted json string.
+fn unescape(quoted: &str) -> Option<String> {
+    match quoted.parse::<sqlx::types::JsonValue>() {
+        Ok(sqlx::types::JsonValue::String(s)) => Some(s),
+        _ => None,
+    }
+}
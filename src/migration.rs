@@ -0,0 +1,227 @@
+use std::fs;
+use std::path::Path;
+
+use crate::error::Context;
+use crate::{Result, RssqlError, UnifiedDatabase};
+
+/// The table recording the applied migrations.
+static MIGRATIONS_TABLE: &str = "_rssql_migrations";
+
+/// One migration, the `down` script is optional (up-only migration).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Migration {
+    pub version: i64,
+    pub name: String,
+    pub up: String,
+    pub down: Option<String>,
+}
+
+/// The direction of the migration file.
+enum Direction {
+    Up,
+    Down,
+}
+
+/// Parse the file name `{version}_{name}.sql`, `{version}_{name}.up.sql` or `{version}_{name}.down.sql`.
+fn parse_file_name(file_name: &str) -> Result<(i64, String, Direction)> {
+    let stem = file_name.strip_suffix(".sql").ok_or_else(|| {
+        RssqlError::ParseError(format!("the migration {} is not a .sql file", file_name))
+    })?;
+    let (stem, direction) = match stem.strip_suffix(".down") {
+        Some(stem) => (stem, Direction::Down),
+        None => (stem.strip_suffix(".up").unwrap_or(stem), Direction::Up),
+    };
+    let (version, name) = stem.split_once('_').ok_or_else(|| {
+        RssqlError::ParseError(format!(
+            "the migration {} is not named as {{version}}_{{name}}.sql",
+            file_name
+        ))
+    })?;
+    let version = version.parse().map_err(|_| {
+        RssqlError::ParseError(format!("invalid version of the migration {}", file_name))
+    })?;
+    Ok((version, name.to_string(), direction))
+}
+
+/// Apply the migrations in the order of version and record them in the `_rssql_migrations` table
+/// (`version`, `name` and `applied_at`).
+///
+/// The migrations are named as `{version}_{name}.sql` for the up-only migrations, or as the pair
+/// `{version}_{name}.up.sql` and `{version}_{name}.down.sql`.
+///
+/// ```
+/// use rssql::{MigrationRunner, UnifiedDatabase};
+/// async fn test_migrate() {
+///     let db = UnifiedDatabase::connect("sqlite:sqlite_test.db?mode=rwc").await.unwrap();
+///     let mut runner = MigrationRunner::new(db)
+///         .migrations_dir("migrations")
+///         .unwrap()
+///         .embedded(&[("0002_add_index.sql", "CREATE INDEX idx_name ON info (name)")])
+///         .unwrap();
+///     let applied = runner.run_pending().await.unwrap();
+///     println!("{:?}", applied);
+/// }
+/// ```
+pub struct MigrationRunner {
+    db: UnifiedDatabase,
+    migrations: Vec<Migration>,
+}
+
+impl MigrationRunner {
+    pub fn new(db: UnifiedDatabase) -> MigrationRunner {
+        MigrationRunner {
+            db,
+            migrations: Vec::new(),
+        }
+    }
+    /// Add the migration, returns an error if the version is already added.
+    pub fn add_migration(mut self, migration: Migration) -> Result<Self> {
+        if self
+            .migrations
+            .iter()
+            .any(|m| m.version == migration.version)
+        {
+            return Err(RssqlError::InvalidArgument(format!(
+                "the migration version {} is duplicated",
+                migration.version
+            )));
+        }
+        self.migrations.push(migration);
+        Ok(self)
+    }
+    /// Add the migrations from the `(file name, sql)` pairs, such as the sql embedded by `include_str!`.
+    pub fn embedded(mut self, files: &[(&str, &str)]) -> Result<Self> {
+        let mut downs = Vec::new();
+        for (file_name, sql) in files {
+            let (version, name, direction) = parse_file_name(file_name)?;
+            match direction {
+                Direction::Up => {
+                    self = self.add_migration(Migration {
+                        version,
+                        name,
+                        up: sql.to_string(),
+                        down: None,
+                    })?
+                }
+                Direction::Down => downs.push((version, file_name, sql)),
+            }
+        }
+        for (version, file_name, sql) in downs {
+            let migration = self
+                .migrations
+                .iter_mut()
+                .find(|m| m.version == version)
+                .ok_or_else(|| {
+                    RssqlError::InvalidArgument(format!(
+                        "the migration {} has no up script",
+                        file_name
+                    ))
+                })?;
+            migration.down = Some(sql.to_string());
+        }
+        Ok(self)
+    }
+    /// Add the migrations from the `.sql` files in the directory, the other files are ignored.
+    pub fn migrations_dir<P: AsRef<Path>>(self, dir: P) -> Result<Self> {
+        let mut files = Vec::new();
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            let file_name = match path.file_name().and_then(|name| name.to_str()) {
+                Some(file_name) if file_name.ends_with(".sql") => file_name.to_string(),
+                _ => continue,
+            };
+            files.push((file_name, fs::read_to_string(&path)?));
+        }
+        let files: Vec<(&str, &str)> = files
+            .iter()
+            .map(|(file_name, sql)| (file_name.as_str(), sql.as_str()))
+            .collect();
+        self.embedded(&files)
+    }
+    async fn create_table(&mut self) -> Result<()> {
+        let sql = format!(
+            "CREATE TABLE IF NOT EXISTS {} (version INTEGER PRIMARY KEY NOT NULL, name TEXT NOT NULL, applied_at TIMESTAMP NOT NULL)",
+            MIGRATIONS_TABLE
+        );
+        self.db.execute(&sql).await?;
+        Ok(())
+    }
+    /// Returns the versions of the applied migrations in ascending order.
+    pub async fn applied_versions(&mut self) -> Result<Vec<i64>> {
+        self.create_table().await?;
+        let sql = format!("SELECT version FROM {} ORDER BY version", MIGRATIONS_TABLE);
+        let rets = self.db.execute_fetch_all(&sql).await?;
+        let mut versions = Vec::new();
+        for row in rets.iter() {
+            let version = row
+                .get("version")
+                .ok_or_else(|| RssqlError::ColumnNotFound("version".to_string()))?;
+            versions.push(i64::try_from(version.clone())?);
+        }
+        Ok(versions)
+    }
+    /// Apply the migrations which are not applied yet, returns their names in the order applied.
+    ///
+    /// Each migration is applied in a transaction together with its record,
+    /// note that mysql commits the DDL statements implicitly.
+    pub async fn run_pending(&mut self) -> Result<Vec<String>> {
+        let applied = self.applied_versions().await?;
+        let mut pending: Vec<&Migration> = self
+            .migrations
+            .iter()
+            .filter(|m| !applied.contains(&m.version))
+            .collect();
+        pending.sort_by_key(|m| m.version);
+        let mut names = Vec::new();
+        for migration in pending {
+            let sql = format!(
+                "{}\n;\nINSERT INTO {} (version, name, applied_at) VALUES ({}, '{}', CURRENT_TIMESTAMP)",
+                migration.up,
+                MIGRATIONS_TABLE,
+                migration.version,
+                migration.name.replace('\'', "''")
+            );
+            self.db
+                .execute_batch_transactional(&sql)
+                .await
+                .with_context(|| format!("the migration {} failed", migration.name))?;
+            names.push(migration.name.clone());
+        }
+        Ok(names)
+    }
+    /// Revert the last applied migration by its down script, returns its name or `None` if nothing is applied.
+    ///
+    /// Returns an error if the migration is up-only or not added to the runner.
+    pub async fn revert_last(&mut self) -> Result<Option<String>> {
+        let version = match self.applied_versions().await?.pop() {
+            Some(version) => version,
+            None => return Ok(None),
+        };
+        let migration = self
+            .migrations
+            .iter()
+            .find(|m| m.version == version)
+            .ok_or_else(|| {
+                RssqlError::InvalidArgument(format!(
+                    "the applied migration {} is not found",
+                    version
+                ))
+            })?;
+        let down = migration.down.as_ref().ok_or_else(|| {
+            RssqlError::InvalidArgument(format!("the migration {} is up-only", migration.name))
+        })?;
+        let sql = format!(
+            "{}\n;\nDELETE FROM {} WHERE version = {}",
+            down, MIGRATIONS_TABLE, version
+        );
+        self.db
+            .execute_batch_transactional(&sql)
+            .await
+            .with_context(|| format!("the migration {} failed", migration.name))?;
+        Ok(Some(migration.name.clone()))
+    }
+    /// Returns the database back.
+    pub fn into_inner(self) -> UnifiedDatabase {
+        self.db
+    }
+}
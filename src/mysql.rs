@@ -1,19 +1,37 @@
-use anyhow;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use sqlx::mysql::{
+    MySql, MySqlArguments, MySqlConnectOptions, MySqlConnection, MySqlRow, MySqlSslMode,
+};
+use sqlx::query::Query;
 use sqlx::types::chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime};
 use sqlx::types::{BigDecimal, JsonValue, Uuid};
-use sqlx::{Column, Row, TypeInfo};
-use sqlx::mysql::MySqlRow;
+use sqlx::{Column, Connection, Row, TypeInfo};
 use std::collections::HashMap;
 use std::fmt;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::Duration;
 
+use crate::JsonKey;
+use crate::OrderKey;
+use crate::Result;
+use crate::RssqlError;
 use crate::SQLDataTypes;
+use crate::SQLParam;
 use crate::SQLRets;
+use crate::SortKey;
+use crate::TlsMode;
 use crate::BINARY;
+use crate::NULL;
 use crate::UNKNOWN;
 
+/// The floats are compared and hashed by the bits, see `SQLDataTypes`.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum MySQLDataTypes {
     /// From https://docs.rs/sqlx-mysql/0.7.0/sqlx_mysql/types/index.html
+    Null,
     Bool(bool),
     I8(i8),
     I16(i16),
@@ -34,11 +52,31 @@ pub enum MySQLDataTypes {
     BigDecimal(BigDecimal),
     Uuid(Uuid),
     JsonValue(JsonValue),
+    /// The value of the `ENUM` column.
+    ///
+    /// The `SET` column is returned as the comma separated `String`,
+    /// since the server reports it as `CHAR` with a flag that sqlx does not expose.
+    Enum(String),
+    /// The value of the `YEAR` column, 1901 to 2155 or 0 for the zero year.
+    Year(u16),
 }
 
+impl_eq_hash!(
+    MySQLDataTypes,
+    unit: [Null],
+    plain: [
+        Bool, I8, I16, I32, I64, U8, U16, U32, U64, String, Binary, DateTime, NaiveDateTime,
+        NaiveDate, NaiveTime, BigDecimal, Uuid, Enum, Year
+    ],
+    keyed: [F32 => |v| v.to_bits(), F64 => |v| v.to_bits(), JsonValue => |v| JsonKey(v)]
+);
+
+impl_ord!(MySQLDataTypes);
+
 impl fmt::Display for MySQLDataTypes {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
+            MySQLDataTypes::Null => write!(f, "{}", NULL),
             MySQLDataTypes::Bool(v) => write!(f, "{}", v),
             MySQLDataTypes::I8(v) => write!(f, "{}", v),
             MySQLDataTypes::I16(v) => write!(f, "{}", v),
@@ -59,14 +97,298 @@ impl fmt::Display for MySQLDataTypes {
             MySQLDataTypes::BigDecimal(v) => write!(f, "{}", v),
             MySQLDataTypes::Uuid(v) => write!(f, "{}", v),
             MySQLDataTypes::JsonValue(v) => write!(f, "{}", v),
+            MySQLDataTypes::Enum(v) => write!(f, "{}", v),
+            MySQLDataTypes::Year(v) => write!(f, "{:04}", v),
+        }
+    }
+}
+
+impl MySQLDataTypes {
+    /// Returns the mysql (mariadb) type name of the value, such as `BOOLEAN`.
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            MySQLDataTypes::Null => "NULL",
+            MySQLDataTypes::Bool(_) => "BOOLEAN",
+            MySQLDataTypes::I8(_) => "TINYINT",
+            MySQLDataTypes::I16(_) => "SMALLINT",
+            MySQLDataTypes::I32(_) => "INT",
+            MySQLDataTypes::I64(_) => "BIGINT",
+            MySQLDataTypes::U8(_) => "TINYINT UNSIGNED",
+            MySQLDataTypes::U16(_) => "SMALLINT UNSIGNED",
+            MySQLDataTypes::U32(_) => "INT UNSIGNED",
+            MySQLDataTypes::U64(_) => "BIGINT UNSIGNED",
+            MySQLDataTypes::F32(_) => "FLOAT",
+            MySQLDataTypes::F64(_) => "DOUBLE",
+            MySQLDataTypes::String(_) => "VARCHAR",
+            MySQLDataTypes::Binary(_) => "VARBINARY",
+            MySQLDataTypes::DateTime(_) => "TIMESTAMP",
+            MySQLDataTypes::NaiveDateTime(_) => "DATETIME",
+            MySQLDataTypes::NaiveDate(_) => "DATE",
+            MySQLDataTypes::NaiveTime(_) => "TIME",
+            MySQLDataTypes::BigDecimal(_) => "DECIMAL",
+            MySQLDataTypes::Uuid(_) => "BYTE(16)",
+            MySQLDataTypes::JsonValue(_) => "JSON",
+            MySQLDataTypes::Enum(_) => "ENUM",
+            MySQLDataTypes::Year(_) => "YEAR",
+        }
+    }
+    pub(crate) fn order_key(&self) -> OrderKey<'_> {
+        match self {
+            MySQLDataTypes::Null => OrderKey::Null,
+            MySQLDataTypes::Bool(v) => OrderKey::Bool(*v),
+            MySQLDataTypes::I8(v) => OrderKey::Integer(i128::from(*v)),
+            MySQLDataTypes::I16(v) => OrderKey::Integer(i128::from(*v)),
+            MySQLDataTypes::I32(v) => OrderKey::Integer(i128::from(*v)),
+            MySQLDataTypes::I64(v) => OrderKey::Integer(i128::from(*v)),
+            MySQLDataTypes::U8(v) => OrderKey::Integer(i128::from(*v)),
+            MySQLDataTypes::U16(v) => OrderKey::Integer(i128::from(*v)),
+            MySQLDataTypes::U32(v) => OrderKey::Integer(i128::from(*v)),
+            MySQLDataTypes::U64(v) => OrderKey::Integer(i128::from(*v)),
+            MySQLDataTypes::Year(v) => OrderKey::Integer(i128::from(*v)),
+            MySQLDataTypes::F32(v) => OrderKey::Float(f64::from(*v)),
+            MySQLDataTypes::F64(v) => OrderKey::Float(*v),
+            MySQLDataTypes::BigDecimal(v) => OrderKey::Decimal(v),
+            MySQLDataTypes::String(v) if v == UNKNOWN => OrderKey::Other,
+            MySQLDataTypes::String(v) | MySQLDataTypes::Enum(v) => OrderKey::String(v),
+            MySQLDataTypes::Binary(v) => OrderKey::Binary(v),
+            MySQLDataTypes::DateTime(v) => OrderKey::DateTime(v.naive_utc()),
+            MySQLDataTypes::NaiveDateTime(v) => OrderKey::DateTime(*v),
+            MySQLDataTypes::NaiveDate(v) => OrderKey::DateTime(v.and_time(NaiveTime::MIN)),
+            MySQLDataTypes::NaiveTime(v) => OrderKey::Time(*v),
+            _ => OrderKey::Other,
+        }
+    }
+    pub(crate) fn sort_key(&self) -> SortKey {
+        match self {
+            MySQLDataTypes::Bool(v) => SortKey::Number(f64::from(u8::from(*v))),
+            MySQLDataTypes::I8(v) => SortKey::Number(*v as f64),
+            MySQLDataTypes::I16(v) => SortKey::Number(*v as f64),
+            MySQLDataTypes::I32(v) => SortKey::Number(*v as f64),
+            MySQLDataTypes::I64(v) => SortKey::Number(*v as f64),
+            MySQLDataTypes::U8(v) => SortKey::Number(*v as f64),
+            MySQLDataTypes::U16(v) => SortKey::Number(*v as f64),
+            MySQLDataTypes::U32(v) => SortKey::Number(*v as f64),
+            MySQLDataTypes::U64(v) => SortKey::Number(*v as f64),
+            MySQLDataTypes::Year(v) => SortKey::Number(f64::from(*v)),
+            MySQLDataTypes::F32(v) => SortKey::Number(*v as f64),
+            MySQLDataTypes::F64(v) => SortKey::Number(*v),
+            MySQLDataTypes::BigDecimal(v) => v
+                .to_string()
+                .parse()
+                .map_or(SortKey::Other, SortKey::Number),
+            MySQLDataTypes::String(v) if v != UNKNOWN => SortKey::String(v.clone()),
+            MySQLDataTypes::Enum(v) => SortKey::String(v.clone()),
+            MySQLDataTypes::DateTime(v) => SortKey::DateTime(v.naive_utc()),
+            MySQLDataTypes::NaiveDateTime(v) => SortKey::DateTime(*v),
+            MySQLDataTypes::NaiveDate(v) => SortKey::DateTime(v.and_time(NaiveTime::MIN)),
+            MySQLDataTypes::NaiveTime(v) => SortKey::Time(*v),
+            _ => SortKey::Other,
+        }
+    }
+    pub(crate) fn to_json_value(&self) -> JsonValue {
+        match self {
+            MySQLDataTypes::Null => JsonValue::Null,
+            MySQLDataTypes::Bool(v) => JsonValue::from(*v),
+            MySQLDataTypes::I8(v) => JsonValue::from(*v),
+            MySQLDataTypes::I16(v) => JsonValue::from(*v),
+            MySQLDataTypes::I32(v) => JsonValue::from(*v),
+            MySQLDataTypes::I64(v) => JsonValue::from(*v),
+            MySQLDataTypes::U8(v) => JsonValue::from(*v),
+            MySQLDataTypes::U16(v) => JsonValue::from(*v),
+            MySQLDataTypes::U32(v) => JsonValue::from(*v),
+            MySQLDataTypes::U64(v) => JsonValue::from(*v),
+            MySQLDataTypes::Year(v) => JsonValue::from(*v),
+            MySQLDataTypes::F32(v) => JsonValue::from(*v),
+            MySQLDataTypes::F64(v) => JsonValue::from(*v),
+            MySQLDataTypes::String(v) => JsonValue::from(v.as_str()),
+            MySQLDataTypes::Binary(v) => JsonValue::from(STANDARD.encode(v)),
+            MySQLDataTypes::JsonValue(v) => v.clone(),
+            MySQLDataTypes::Enum(v) => JsonValue::from(v.as_str()),
+            _ => JsonValue::from(self.to_string()),
+        }
+    }
+    pub(crate) fn is_null(&self) -> bool {
+        matches!(self, MySQLDataTypes::Null)
+    }
+    pub(crate) fn is_numeric_or_bool(&self) -> bool {
+        matches!(
+            self,
+            MySQLDataTypes::Bool(_)
+                | MySQLDataTypes::I8(_)
+                | MySQLDataTypes::I16(_)
+                | MySQLDataTypes::I32(_)
+                | MySQLDataTypes::I64(_)
+                | MySQLDataTypes::U8(_)
+                | MySQLDataTypes::U16(_)
+                | MySQLDataTypes::U32(_)
+                | MySQLDataTypes::U64(_)
+                | MySQLDataTypes::Year(_)
+                | MySQLDataTypes::F32(_)
+                | MySQLDataTypes::F64(_)
+                | MySQLDataTypes::BigDecimal(_)
+        )
+    }
+}
+
+/// The charsets allowed by `MySQL::set_charset` and `MySQLConnectOptions::charset`, with their collation.
+static CHARSETS: [(&str, &str); 12] = [
+    ("utf8mb4", "utf8mb4_general_ci"),
+    ("utf8mb3", "utf8mb3_general_ci"),
+    ("utf8", "utf8_general_ci"),
+    ("latin1", "latin1_swedish_ci"),
+    ("latin2", "latin2_general_ci"),
+    ("ascii", "ascii_general_ci"),
+    ("binary", "binary"),
+    ("gbk", "gbk_chinese_ci"),
+    ("big5", "big5_chinese_ci"),
+    ("sjis", "sjis_japanese_ci"),
+    ("euckr", "euckr_korean_ci"),
+    ("cp1251", "cp1251_general_ci"),
+];
+
+/// Returns the collation of the charset, returns an error if the charset is not allowed.
+pub(crate) fn charset_collation(charset: &str) -> Result<&'static str> {
+    CHARSETS
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case(charset))
+        .map(|(_, collation)| *collation)
+        .ok_or_else(|| {
+            RssqlError::InvalidArgument(format!("unsupported mysql charset: {}", charset))
+        })
+}
+
+/// The options to connect to mysql (mariadb).
+#[derive(Debug, Clone)]
+pub struct MySQLConnectOptions {
+    url: String,
+    connect_timeout: Option<Duration>,
+    statement_timeout: Option<Duration>,
+    tls: Option<TlsMode>,
+    client_cert: Option<(PathBuf, PathBuf)>,
+    charset: Option<String>,
+    #[cfg(feature = "tracing")]
+    with_warnings: bool,
+}
+
+impl MySQLConnectOptions {
+    pub fn new(url: &str) -> MySQLConnectOptions {
+        MySQLConnectOptions {
+            url: url.to_string(),
+            connect_timeout: None,
+            statement_timeout: None,
+            tls: None,
+            client_cert: None,
+            charset: None,
+            #[cfg(feature = "tracing")]
+            with_warnings: false,
+        }
+    }
+    /// Give up the connection attempt after the timeout.
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+    /// Abort the statement running longer than the timeout, it is set by `SET SESSION max_execution_time`
+    /// after connecting, note that mysql only applies it to `SELECT`.
+    /// The `max_statement_time` is set instead on mariadb, which applies to all the statements.
+    pub fn statement_timeout(mut self, timeout: Duration) -> Self {
+        self.statement_timeout = Some(timeout);
+        self
+    }
+    /// Set the tls mode, the mode in the url (if any) is used if not set.
+    pub fn tls(mut self, tls: TlsMode) -> Self {
+        self.tls = Some(tls);
+        self
+    }
+    /// Authenticate with the client certificate and key files.
+    pub fn client_cert(mut self, cert: PathBuf, key: PathBuf) -> Self {
+        self.client_cert = Some((cert, key));
+        self
+    }
+    /// Set the charset of the session (`SET NAMES`) right after connecting, such as `utf8mb4` or `latin1`.
+    ///
+    /// The charset is checked when connecting, see `MySQL::set_charset` for the allowed ones.
+    pub fn charset(mut self, charset: &str) -> Self {
+        self.charset = Some(charset.to_string());
+        self
+    }
+    /// Fetch the warnings by `SHOW WARNINGS` after every `execute`, `execute_fetch_all` and `execute_fetch_one`
+    /// and log them by `tracing::warn!`.
+    #[cfg(feature = "tracing")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "tracing")))]
+    pub fn with_warnings(mut self, with_warnings: bool) -> Self {
+        self.with_warnings = with_warnings;
+        self
+    }
+    #[cfg(feature = "tracing")]
+    pub(crate) fn warnings_enabled(&self) -> bool {
+        self.with_warnings
+    }
+    pub(crate) fn set_url(&mut self, url: &str) {
+        self.url = url.to_string();
+    }
+    /// Connect and returns the connection with whether the server is mariadb.
+    pub(crate) async fn connect(&self) -> Result<(MySqlConnection, bool)> {
+        let mut options = MySqlConnectOptions::from_str(&self.url)?;
+        options = match &self.tls {
+            Some(TlsMode::Disabled) => options.ssl_mode(MySqlSslMode::Disabled),
+            Some(TlsMode::Preferred) => options.ssl_mode(MySqlSslMode::Preferred),
+            Some(TlsMode::Required) => options.ssl_mode(MySqlSslMode::Required),
+            Some(TlsMode::VerifyCa(ca)) => options.ssl_mode(MySqlSslMode::VerifyCa).ssl_ca(ca),
+            Some(TlsMode::VerifyFull(ca)) => {
+                options.ssl_mode(MySqlSslMode::VerifyIdentity).ssl_ca(ca)
+            }
+            None => options,
+        };
+        if let Some((cert, key)) = &self.client_cert {
+            options = options.ssl_client_cert(cert).ssl_client_key(key);
+        }
+        if let Some(charset) = &self.charset {
+            let collation = charset_collation(charset)?;
+            options = options.charset(charset).collation(collation);
+        }
+        let connect = MySqlConnection::connect_with(&options);
+        let mut connection = crate::with_timeout(self.connect_timeout, connect).await?;
+        let version: String = sqlx::query_scalar("SELECT VERSION()")
+            .fetch_one(&mut connection)
+            .await?;
+        // the version of mariadb is such as `10.11.6-MariaDB`.
+        let mariadb = version.contains("MariaDB");
+        if let Some(timeout) = self.statement_timeout {
+            let (variable, value) = execution_time(mariadb, timeout);
+            let sql = format!("SET SESSION {} = {}", variable, value);
+            sqlx::query(&sql).execute(&mut connection).await?;
         }
+        Ok((connection, mariadb))
     }
 }
 
-pub async fn rows_process(rows: Vec<MySqlRow>) -> anyhow::Result<SQLRets> {
-    let mut sql_rets = SQLRets::new();
+/// Returns the session variable of the statement timeout and its value,
+/// `max_execution_time` in milliseconds for mysql and `max_statement_time` in seconds for mariadb.
+pub(crate) fn execution_time(mariadb: bool, timeout: Duration) -> (&'static str, String) {
+    match mariadb {
+        true => (
+            "max_statement_time",
+            timeout.as_secs_f64().max(0.001).to_string(),
+        ),
+        false => ("max_execution_time", timeout.as_millis().max(1).to_string()),
+    }
+}
+
+/// One row of `SHOW WARNINGS`, see `MySQL::fetch_warnings`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MySQLWarning {
+    /// `Note`, `Warning` or `Error`.
+    pub level: String,
+    pub code: u32,
+    pub message: String,
+}
 
-    if rows.len() > 0 {
+pub async fn rows_process(rows: Vec<MySqlRow>) -> Result<SQLRets> {
+    let columns = rows.first().map_or(0, |row| row.len());
+    let mut sql_rets = SQLRets::with_capacity(columns, rows.len());
+
+    if !rows.is_empty() {
         // push all column
         let mysql_row = &rows[0];
         let mysql_row_len = mysql_row.len();
@@ -78,100 +400,217 @@ pub async fn rows_process(rows: Vec<MySqlRow>) -> anyhow::Result<SQLRets> {
     }
 
     for mysql_row in &rows {
-        let mut sql_row: HashMap<String, SQLDataTypes> = HashMap::new();
-        let mysql_row_len = mysql_row.len();
-
-        for i in 0..mysql_row_len {
-            let col = mysql_row.column(i);
-            let col_name = col.name().to_string();
-            let type_info = col.type_info();
-            let mysql_value = match type_info.name() {
-                "BOOLEAN" | "TINYINT(1)" => {
-                    let value: bool = mysql_row.get(i);
-                    MySQLDataTypes::Bool(value)
-                }
-                "TINYINT" => {
-                    let value: i8 = mysql_row.get(i);
-                    MySQLDataTypes::I8(value)
-                }
-                "SMALLINT" => {
-                    let value: i16 = mysql_row.get(i);
-                    MySQLDataTypes::I16(value)
-                }
-                "INT" => {
-                    let value: i32 = mysql_row.get(i);
-                    MySQLDataTypes::I32(value)
-                }
-                "BIGINT" => {
-                    let value: i64 = mysql_row.get(i);
-                    MySQLDataTypes::I64(value)
-                }
-                "TINYINT UNSIGNED" => {
-                    let value: u8 = mysql_row.get(i);
-                    MySQLDataTypes::U8(value)
-                }
-                "SMALLINT UNSIGNED" => {
-                    let value: u16 = mysql_row.get(i);
-                    MySQLDataTypes::U16(value)
-                }
-                "INT UNSIGNED" => {
-                    let value: u32 = mysql_row.get(i);
-                    MySQLDataTypes::U32(value)
-                }
-                "BIGINT UNSIGNED" => {
-                    let value: u64 = mysql_row.get(i);
-                    MySQLDataTypes::U64(value)
-                }
-                "FLOAT" => {
-                    let value: f32 = mysql_row.get(i);
-                    MySQLDataTypes::F32(value)
-                }
-                "DOUBLE" => {
-                    let value: f64 = mysql_row.get(i);
-                    MySQLDataTypes::F64(value)
-                }
-                "VARCHAR" | "CHAR" | "TEXT" => {
-                    let value: String = mysql_row.get(i);
-                    MySQLDataTypes::String(value)
-                }
-                "VARBINARY" | "BINARY" | "BLOB" => {
-                    let value: Vec<u8> = mysql_row.get(i);
-                    MySQLDataTypes::Binary(value)
-                }
-                "TIMESTAMP" => {
-                    let value: DateTime<chrono::Utc> = mysql_row.get(i);
-                    MySQLDataTypes::DateTime(value)
-                }
-                "DATETIME" => {
-                    let value: NaiveDateTime = mysql_row.get(i);
-                    MySQLDataTypes::NaiveDateTime(value)
-                }
-                "DATE" => {
-                    let value: NaiveDate = mysql_row.get(i);
-                    MySQLDataTypes::NaiveDate(value)
-                }
-                "TIME" => {
-                    let value: NaiveTime = mysql_row.get(i);
-                    MySQLDataTypes::NaiveTime(value)
-                }
-                "DECIMAL" => {
-                    let value: BigDecimal = mysql_row.get(i);
-                    MySQLDataTypes::BigDecimal(value)
-                }
-                "BYTE(16)" => {
-                    let value: Uuid = mysql_row.get(i);
-                    MySQLDataTypes::Uuid(value)
-                }
-                "JSON" => {
-                    let value: JsonValue = mysql_row.get(i);
-                    MySQLDataTypes::JsonValue(value)
-                }
-                _ => MySQLDataTypes::String(UNKNOWN.into()),
-            };
-            let sql_value = SQLDataTypes::MySQLDataTypes(mysql_value);
-            sql_row.insert(col_name, sql_value);
-        }
+        let sql_row = row_process(mysql_row)?;
         sql_rets.push_rets(sql_row);
     }
     Ok(sql_rets)
 }
+
+pub fn row_process(mysql_row: &MySqlRow) -> Result<HashMap<String, SQLDataTypes>> {
+    let mysql_row_len = mysql_row.len();
+    let mut sql_row: HashMap<String, SQLDataTypes> = HashMap::with_capacity(mysql_row_len);
+
+    for i in 0..mysql_row_len {
+        let col = mysql_row.column(i);
+        let col_name = col.name().to_string();
+        let type_info = col.type_info();
+        let mysql_value = match type_info.name() {
+            "BOOLEAN" | "TINYINT(1)" => {
+                let value: Option<bool> = mysql_row.try_get(i)?;
+                value.map_or(MySQLDataTypes::Null, MySQLDataTypes::Bool)
+            }
+            "TINYINT" => {
+                let value: Option<i8> = mysql_row.try_get(i)?;
+                value.map_or(MySQLDataTypes::Null, MySQLDataTypes::I8)
+            }
+            "SMALLINT" => {
+                let value: Option<i16> = mysql_row.try_get(i)?;
+                value.map_or(MySQLDataTypes::Null, MySQLDataTypes::I16)
+            }
+            "MEDIUMINT" | "INT" => {
+                let value: Option<i32> = mysql_row.try_get(i)?;
+                value.map_or(MySQLDataTypes::Null, MySQLDataTypes::I32)
+            }
+            "BIGINT" => {
+                let value: Option<i64> = mysql_row.try_get(i)?;
+                value.map_or(MySQLDataTypes::Null, MySQLDataTypes::I64)
+            }
+            "TINYINT UNSIGNED" => {
+                let value: Option<u8> = mysql_row.try_get(i)?;
+                value.map_or(MySQLDataTypes::Null, MySQLDataTypes::U8)
+            }
+            "SMALLINT UNSIGNED" => {
+                let value: Option<u16> = mysql_row.try_get(i)?;
+                value.map_or(MySQLDataTypes::Null, MySQLDataTypes::U16)
+            }
+            "MEDIUMINT UNSIGNED" | "INT UNSIGNED" => {
+                let value: Option<u32> = mysql_row.try_get(i)?;
+                value.map_or(MySQLDataTypes::Null, MySQLDataTypes::U32)
+            }
+            "BIGINT UNSIGNED" => {
+                let value: Option<u64> = mysql_row.try_get(i)?;
+                value.map_or(MySQLDataTypes::Null, MySQLDataTypes::U64)
+            }
+            "YEAR" => {
+                // mariadb does not always flag the year column as unsigned which sqlx checks.
+                let value: Option<u16> = mysql_row.try_get_unchecked(i)?;
+                value.map_or(MySQLDataTypes::Null, MySQLDataTypes::Year)
+            }
+            "FLOAT" => {
+                let value: Option<f32> = mysql_row.try_get(i)?;
+                value.map_or(MySQLDataTypes::Null, MySQLDataTypes::F32)
+            }
+            "DOUBLE" => {
+                let value: Option<f64> = mysql_row.try_get(i)?;
+                value.map_or(MySQLDataTypes::Null, MySQLDataTypes::F64)
+            }
+            "VARCHAR" | "CHAR" | "TEXT" => {
+                let value: Option<String> = mysql_row.try_get(i)?;
+                value.map_or(MySQLDataTypes::Null, MySQLDataTypes::String)
+            }
+            "VARBINARY" | "BINARY" | "BLOB" => {
+                let value: Option<Vec<u8>> = mysql_row.try_get(i)?;
+                value.map_or(MySQLDataTypes::Null, MySQLDataTypes::Binary)
+            }
+            "TIMESTAMP" => {
+                let value: Option<DateTime<chrono::Utc>> = mysql_row.try_get(i)?;
+                value.map_or(MySQLDataTypes::Null, MySQLDataTypes::DateTime)
+            }
+            "DATETIME" => {
+                let value: Option<NaiveDateTime> = mysql_row.try_get(i)?;
+                value.map_or(MySQLDataTypes::Null, MySQLDataTypes::NaiveDateTime)
+            }
+            "DATE" => {
+                let value: Option<NaiveDate> = mysql_row.try_get(i)?;
+                value.map_or(MySQLDataTypes::Null, MySQLDataTypes::NaiveDate)
+            }
+            "TIME" => {
+                let value: Option<NaiveTime> = mysql_row.try_get(i)?;
+                value.map_or(MySQLDataTypes::Null, MySQLDataTypes::NaiveTime)
+            }
+            "DECIMAL" => {
+                let value: Option<BigDecimal> = mysql_row.try_get(i)?;
+                value.map_or(MySQLDataTypes::Null, MySQLDataTypes::BigDecimal)
+            }
+            "BYTE(16)" => {
+                let value: Option<Uuid> = mysql_row.try_get(i)?;
+                value.map_or(MySQLDataTypes::Null, MySQLDataTypes::Uuid)
+            }
+            "JSON" => {
+                let value: Option<JsonValue> = mysql_row.try_get(i)?;
+                value.map_or(MySQLDataTypes::Null, MySQLDataTypes::JsonValue)
+            }
+            "ENUM" => {
+                let value: Option<String> = mysql_row.try_get_unchecked(i)?;
+                value.map_or(MySQLDataTypes::Null, MySQLDataTypes::Enum)
+            }
+            _ => MySQLDataTypes::String(UNKNOWN.into()),
+        };
+        let sql_value = SQLDataTypes::MySQLDataTypes(mysql_value);
+        sql_row.insert(col_name, sql_value);
+    }
+    Ok(sql_row)
+}
+
+pub fn bind_params(sql: &str, params: Vec<SQLParam>) -> Result<Query<'_, MySql, MySqlArguments>> {
+    crate::check_params_count(crate::count_question_placeholders(sql), &params)?;
+    let mut query = sqlx::query(sql);
+    for param in params {
+        query = match param {
+            SQLParam::Bool(v) => query.bind(v),
+            SQLParam::I8(v) => query.bind(v),
+            SQLParam::I16(v) => query.bind(v),
+            SQLParam::I32(v) => query.bind(v),
+            SQLParam::I64(v) => query.bind(v),
+            SQLParam::U8(v) => query.bind(v),
+            SQLParam::U16(v) => query.bind(v),
+            SQLParam::U32(v) => query.bind(v),
+            SQLParam::U64(v) => query.bind(v),
+            SQLParam::F32(v) => query.bind(v),
+            SQLParam::F64(v) => query.bind(v),
+            SQLParam::String(v) => query.bind(v),
+            SQLParam::Binary(v) => query.bind(v),
+            SQLParam::DateTime(v) => query.bind(v),
+            SQLParam::NaiveDateTime(v) => query.bind(v),
+            SQLParam::NaiveDate(v) => query.bind(v),
+            SQLParam::NaiveTime(v) => query.bind(v),
+            SQLParam::BigDecimal(v) => query.bind(v),
+            SQLParam::Uuid(v) => query.bind(v),
+            SQLParam::JsonValue(v) => query.bind(v),
+        };
+    }
+    Ok(query)
+}
+
+/// Bind the value of the column to the next placeholder.
+pub fn bind_value<'q>(
+    query: Query<'q, MySql, MySqlArguments>,
+    value: &SQLDataTypes,
+) -> Result<Query<'q, MySql, MySqlArguments>> {
+    let value = match value {
+        SQLDataTypes::MySQLDataTypes(v) => v,
+        _ => {
+            return Err(RssqlError::TypeConversionError {
+                from: value.type_name().to_string(),
+                to: "MySQL".to_string(),
+            })
+        }
+    };
+    let query = match value {
+        MySQLDataTypes::Null => query.bind(None::<String>),
+        MySQLDataTypes::Bool(v) => query.bind(*v),
+        MySQLDataTypes::I8(v) => query.bind(*v),
+        MySQLDataTypes::I16(v) => query.bind(*v),
+        MySQLDataTypes::I32(v) => query.bind(*v),
+        MySQLDataTypes::I64(v) => query.bind(*v),
+        MySQLDataTypes::U8(v) => query.bind(*v),
+        MySQLDataTypes::U16(v) | MySQLDataTypes::Year(v) => query.bind(*v),
+        MySQLDataTypes::U32(v) => query.bind(*v),
+        MySQLDataTypes::U64(v) => query.bind(*v),
+        MySQLDataTypes::F32(v) => query.bind(*v),
+        MySQLDataTypes::F64(v) => query.bind(*v),
+        MySQLDataTypes::String(v) | MySQLDataTypes::Enum(v) => query.bind(v.clone()),
+        MySQLDataTypes::Binary(v) => query.bind(v.clone()),
+        MySQLDataTypes::DateTime(v) => query.bind(*v),
+        MySQLDataTypes::NaiveDateTime(v) => query.bind(*v),
+        MySQLDataTypes::NaiveDate(v) => query.bind(*v),
+        MySQLDataTypes::NaiveTime(v) => query.bind(*v),
+        MySQLDataTypes::BigDecimal(v) => query.bind(v.clone()),
+        MySQLDataTypes::Uuid(v) => query.bind(*v),
+        MySQLDataTypes::JsonValue(v) => query.bind(v.clone()),
+    };
+    Ok(query)
+}
+
+/// The max number of the placeholders in one prepared statement.
+const MAX_PLACEHOLDERS: usize = 65535;
+
+/// The number of the rows inserted by one statement, so that the packet of the statement
+/// does not exceed `max_packet_size` and the placeholders do not exceed the limit.
+pub fn insert_chunk_size(
+    max_packet_size: usize,
+    columns: usize,
+    rows: &[Vec<SQLDataTypes>],
+) -> usize {
+    // The header of the value and the placeholder `?,` in the sql.
+    const OVERHEAD: usize = 12;
+    let row_size = rows
+        .iter()
+        .map(|row| {
+            row.iter()
+                .map(|value| {
+                    let len = match value {
+                        SQLDataTypes::MySQLDataTypes(MySQLDataTypes::Binary(v)) => v.len(),
+                        SQLDataTypes::MySQLDataTypes(MySQLDataTypes::Null) => 0,
+                        other => other.to_string().len(),
+                    };
+                    len + OVERHEAD
+                })
+                .sum::<usize>()
+        })
+        .max()
+        .unwrap_or(OVERHEAD);
+    (max_packet_size / row_size.max(1))
+        .min(MAX_PLACEHOLDERS / columns.max(1))
+        .max(1)
+}
@@ -0,0 +1,104 @@
+use futures::future::BoxFuture;
+use futures::FutureExt;
+
+use crate::MySQL;
+use crate::PostgreSQL;
+use crate::Result;
+use crate::SQLRets;
+use crate::SQLite;
+
+/// The database which can fetch the rows page by page, used by `PaginatedQuery`.
+pub trait FetchPage {
+    /// Execute and fetch at most `limit` rows after skipping `offset` rows.
+    fn fetch_page<'a>(
+        &'a mut self,
+        sql: &'a str,
+        limit: u64,
+        offset: u64,
+    ) -> BoxFuture<'a, Result<SQLRets>>;
+}
+
+impl FetchPage for SQLite {
+    fn fetch_page<'a>(
+        &'a mut self,
+        sql: &'a str,
+        limit: u64,
+        offset: u64,
+    ) -> BoxFuture<'a, Result<SQLRets>> {
+        self.execute_fetch_all_paginated(sql, limit, offset).boxed()
+    }
+}
+
+impl FetchPage for MySQL {
+    fn fetch_page<'a>(
+        &'a mut self,
+        sql: &'a str,
+        limit: u64,
+        offset: u64,
+    ) -> BoxFuture<'a, Result<SQLRets>> {
+        self.execute_fetch_all_paginated(sql, limit, offset).boxed()
+    }
+}
+
+impl FetchPage for PostgreSQL {
+    fn fetch_page<'a>(
+        &'a mut self,
+        sql: &'a str,
+        limit: u64,
+        offset: u64,
+    ) -> BoxFuture<'a, Result<SQLRets>> {
+        self.execute_fetch_all_paginated(sql, limit, offset).boxed()
+    }
+}
+
+/// Iterate over the returns of the sql page by page.
+///
+/// ```
+/// use rssql::{PaginatedQuery, SQLite};
+/// async fn test_sqlite_pages() {
+///     let mut sqlite = SQLite::connect("sqlite:sqlite_test.db?mode=rwc").await.unwrap();
+///     let mut query = PaginatedQuery::new("SELECT * FROM info ORDER BY name", 100);
+///     while let Some(rets) = query.next_page(&mut sqlite).await.unwrap() {
+///         println!("{}", rets);
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct PaginatedQuery {
+    sql: String,
+    page: u64,
+    page_size: u64,
+}
+
+impl PaginatedQuery {
+    /// Start from the first page, the sql should have a stable `ORDER BY` to get consistent pages.
+    pub fn new(sql: &str, page_size: u64) -> PaginatedQuery {
+        PaginatedQuery {
+            sql: sql.to_string(),
+            page: 0,
+            page_size,
+        }
+    }
+    /// Start from the page, the first page is `0`.
+    pub fn start_page(mut self, page: u64) -> Self {
+        self.page = page;
+        self
+    }
+    /// The index of the page returned by the next `next_page`.
+    pub fn page(&self) -> u64 {
+        self.page
+    }
+    pub fn page_size(&self) -> u64 {
+        self.page_size
+    }
+    /// Fetch the next page, returns `None` when the page is empty.
+    pub async fn next_page<D: FetchPage>(&mut self, db: &mut D) -> Result<Option<SQLRets>> {
+        let offset = self.page * self.page_size;
+        let rets = db.fetch_page(&self.sql, self.page_size, offset).await?;
+        if rets.is_empty() {
+            return Ok(None);
+        }
+        self.page += 1;
+        Ok(Some(rets))
+    }
+}
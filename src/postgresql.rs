@@ -1,27 +1,49 @@
-use anyhow;
-use sqlx::postgres::types::{PgInterval, PgMoney, PgRange, PgTimeTz, PgLTree, PgLQuery};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use futures::stream::{BoxStream, Stream, StreamExt};
+use sqlx::postgres::types::{
+    PgBox, PgCircle, PgInterval, PgLQuery, PgLSeg, PgLTree, PgLTreeLabel, PgLine, PgMoney, PgPath,
+    PgPoint, PgPolygon, PgRange, PgTimeTz,
+};
+use sqlx::postgres::{
+    PgArguments, PgConnectOptions, PgConnection, PgListener, PgPoolOptions, PgRow, PgSslMode,
+    PgTypeKind, Postgres,
+};
+use sqlx::query::Query;
 use sqlx::types::chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime};
 use sqlx::types::ipnetwork::IpNetwork;
 use sqlx::types::mac_address::MacAddress;
 use sqlx::types::{BigDecimal, BitVec, JsonValue, Uuid};
-use sqlx::{Column, Row, TypeInfo};
-use sqlx::postgres::PgRow;
+use sqlx::{Column, Connection, Row, TypeInfo};
 use std::collections::HashMap;
 use std::fmt;
+use std::ops::{Bound, Deref};
+use std::path::PathBuf;
+use std::pin::Pin;
+use std::str::FromStr;
+use std::task::{Context, Poll};
+use std::time::Duration;
 
+use crate::IterKey;
+use crate::JsonKey;
+use crate::OrderKey;
+use crate::Result;
+use crate::RssqlError;
 use crate::SQLDataTypes;
+use crate::SQLParam;
 use crate::SQLRets;
-use crate::UNKNOWN;
+use crate::SortKey;
+use crate::TlsMode;
 use crate::BINARY;
+use crate::NULL;
+use crate::UNKNOWN;
 
-
-static PGINTERVAL: &str = "[pginterval]";
-static PGMONEY: &str = "[pgmoney]";
-static PGTIMETZ: &str = "[pgtimetz]";
-
+/// The floats are compared and hashed by the bits, see `SQLDataTypes`.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum PostgreSQLDataTypes {
     /// From https://docs.rs/sqlx-postgres/0.7.0/sqlx_postgres/types/index.html
+    Null,
     Bool(bool),
     I8(i8),
     I16(i16),
@@ -36,32 +58,239 @@ pub enum PostgreSQLDataTypes {
     Binary(Vec<u8>),
     Void(()),
     // not impl Display
+    #[cfg_attr(feature = "serde", serde(with = "serde_with::PgIntervalDef"))]
     PgInterval(PgInterval),
+    #[cfg_attr(feature = "serde", serde(with = "serde_with::PgRangeDef"))]
     PgRangeBigDecimal(PgRange<BigDecimal>),
+    #[cfg_attr(feature = "serde", serde(with = "serde_with::PgRangeDef"))]
     PgRangeDateTime(PgRange<DateTime<chrono::Utc>>),
+    #[cfg_attr(feature = "serde", serde(with = "serde_with::PgRangeDef"))]
     PgRangeNaiveDate(PgRange<NaiveDate>),
+    #[cfg_attr(feature = "serde", serde(with = "serde_with::PgRangeDef"))]
     PgRangeNaiveDateTime(PgRange<NaiveDateTime>),
+    #[cfg_attr(feature = "serde", serde(with = "serde_with::PgRangeDef"))]
     PgRangeI32(PgRange<i32>),
+    #[cfg_attr(feature = "serde", serde(with = "serde_with::PgRangeDef"))]
     PgRangeI64(PgRange<i64>),
+    #[cfg_attr(feature = "serde", serde(with = "serde_with::PgMoneyDef"))]
     PgMoney(PgMoney),
+    #[cfg_attr(feature = "serde", serde(with = "serde_with::display_fromstr"))]
     PgLTree(PgLTree),
+    #[cfg_attr(feature = "serde", serde(with = "serde_with::display_fromstr"))]
     PgLQuery(PgLQuery),
     BigDecimal(BigDecimal),
     DateTime(DateTime<chrono::Utc>),
     NaiveDateTime(NaiveDateTime),
     NaiveDate(NaiveDate),
     NaiveTime(NaiveTime),
+    #[cfg_attr(feature = "serde", serde(with = "serde_with::pg_time_tz"))]
     PgTimeTz(PgTimeTz),
     Uuid(Uuid),
     IpNetwork(IpNetwork),
     MacAddress(MacAddress),
     BitVec(BitVec),
     JsonValue(JsonValue),
+    ArrayI32(Vec<i32>),
+    ArrayI64(Vec<i64>),
+    ArrayF64(Vec<f64>),
+    ArrayString(Vec<String>),
+    ArrayBool(Vec<bool>),
+    #[cfg_attr(feature = "serde", serde(with = "serde_with::geometry"))]
+    PgPoint(PgPoint),
+    #[cfg_attr(feature = "serde", serde(with = "serde_with::geometry"))]
+    PgLine(PgLine),
+    #[cfg_attr(feature = "serde", serde(with = "serde_with::geometry"))]
+    PgLSeg(PgLSeg),
+    #[cfg_attr(feature = "serde", serde(with = "serde_with::geometry"))]
+    PgBox(PgBox),
+    #[cfg_attr(feature = "serde", serde(with = "serde_with::geometry"))]
+    PgPath(PgPath),
+    #[cfg_attr(feature = "serde", serde(with = "serde_with::geometry"))]
+    PgPolygon(PgPolygon),
+    #[cfg_attr(feature = "serde", serde(with = "serde_with::geometry"))]
+    PgCircle(PgCircle),
+}
+
+impl_eq_hash!(
+    PostgreSQLDataTypes,
+    unit: [Null],
+    plain: [
+        Bool, I8, I16, I32, I64, U8, U16, U64, String, Binary, Void, BigDecimal, DateTime,
+        NaiveDateTime, NaiveDate, NaiveTime, Uuid, IpNetwork, MacAddress, BitVec, ArrayI32,
+        ArrayI64, ArrayString, ArrayBool
+    ],
+    keyed: [
+        F32 => |v| v.to_bits(),
+        F64 => |v| v.to_bits(),
+        ArrayF64 => |v| IterKey(v.iter().copied().map(f64::to_bits)),
+        PgInterval => |v| (v.months, v.days, v.microseconds),
+        PgRangeBigDecimal => |v| range_key(v),
+        PgRangeDateTime => |v| range_key(v),
+        PgRangeNaiveDate => |v| range_key(v),
+        PgRangeNaiveDateTime => |v| range_key(v),
+        PgRangeI32 => |v| range_key(v),
+        PgRangeI64 => |v| range_key(v),
+        PgMoney => |v| v.0,
+        PgLTree => |v| IterKey(v.iter().map(PgLTreeLabel::deref)),
+        PgLQuery => |v| v.to_string(),
+        PgTimeTz => |v| (v.time, v.offset.local_minus_utc()),
+        JsonValue => |v| JsonKey(v),
+        PgPoint => |v| point_bits(v),
+        PgLine => |v| (v.a.to_bits(), v.b.to_bits(), v.c.to_bits()),
+        PgLSeg => |v| (
+            v.start_x.to_bits(),
+            v.start_y.to_bits(),
+            v.end_x.to_bits(),
+            v.end_y.to_bits()
+        ),
+        PgBox => |v| (
+            v.upper_right_x.to_bits(),
+            v.upper_right_y.to_bits(),
+            v.lower_left_x.to_bits(),
+            v.lower_left_y.to_bits()
+        ),
+        PgPath => |v| (v.closed, IterKey(v.points.iter().map(point_bits))),
+        PgPolygon => |v| IterKey(v.points.iter().map(point_bits)),
+        PgCircle => |v| (v.x.to_bits(), v.y.to_bits(), v.radius.to_bits())
+    ]
+);
+
+/// The key of the bound, which is compared and hashed like the bound and is also `Ord`.
+type BoundKey<'a, T> = (u8, Option<&'a T>);
+
+/// The key of the range by the keys of the bounds.
+fn range_key<T>(range: &PgRange<T>) -> (BoundKey<'_, T>, BoundKey<'_, T>) {
+    fn bound_key<T>(bound: &Bound<T>) -> BoundKey<'_, T> {
+        match bound {
+            Bound::Unbounded => (0, None),
+            Bound::Included(v) => (1, Some(v)),
+            Bound::Excluded(v) => (2, Some(v)),
+        }
+    }
+    (bound_key(&range.start), bound_key(&range.end))
+}
+
+/// The key of the point, the coordinates are compared and hashed by the bits like the floats.
+fn point_bits(point: &PgPoint) -> (u64, u64) {
+    (point.x.to_bits(), point.y.to_bits())
+}
+
+impl_ord!(PostgreSQLDataTypes);
+
+/// The geometric types formatted as the postgresql literal, the same as the postgresql output such as `(1,2)`.
+pub(crate) trait PgGeometry {
+    fn to_literal(&self) -> String;
+}
+
+fn fmt_points(points: &[PgPoint]) -> String {
+    let points: Vec<String> = points.iter().map(|p| p.to_literal()).collect();
+    points.join(",")
+}
+
+impl PgGeometry for PgPoint {
+    fn to_literal(&self) -> String {
+        format!("({},{})", self.x, self.y)
+    }
+}
+
+impl PgGeometry for PgLine {
+    fn to_literal(&self) -> String {
+        format!("{{{},{},{}}}", self.a, self.b, self.c)
+    }
+}
+
+impl PgGeometry for PgLSeg {
+    fn to_literal(&self) -> String {
+        format!(
+            "[({},{}),({},{})]",
+            self.start_x, self.start_y, self.end_x, self.end_y
+        )
+    }
+}
+
+impl PgGeometry for PgBox {
+    fn to_literal(&self) -> String {
+        format!(
+            "({},{}),({},{})",
+            self.upper_right_x, self.upper_right_y, self.lower_left_x, self.lower_left_y
+        )
+    }
+}
+
+impl PgGeometry for PgPath {
+    /// The closed path is in parentheses and the open path is in brackets.
+    fn to_literal(&self) -> String {
+        match self.closed {
+            true => format!("({})", fmt_points(&self.points)),
+            false => format!("[{}]", fmt_points(&self.points)),
+        }
+    }
+}
+
+impl PgGeometry for PgPolygon {
+    fn to_literal(&self) -> String {
+        format!("({})", fmt_points(&self.points))
+    }
+}
+
+impl PgGeometry for PgCircle {
+    fn to_literal(&self) -> String {
+        format!("<({},{}),{}>", self.x, self.y, self.radius)
+    }
+}
+
+/// Format the interval as ISO-8601 duration, such as `P1M2DT3.5S`.
+fn fmt_interval(v: &PgInterval, f: &mut fmt::Formatter) -> fmt::Result {
+    let sign = if v.microseconds < 0 { "-" } else { "" };
+    let microseconds = v.microseconds.unsigned_abs();
+    let seconds = microseconds / 1_000_000;
+    let fraction = microseconds % 1_000_000;
+    write!(f, "P{}M{}DT{}{}", v.months, v.days, sign, seconds)?;
+    if fraction != 0 {
+        let fraction = format!("{:06}", fraction);
+        write!(f, ".{}", fraction.trim_end_matches('0'))?;
+    }
+    write!(f, "S")
+}
+
+/// Format the money (in cents) with two decimal places, such as `-12.05`.
+fn fmt_money(v: &PgMoney, f: &mut fmt::Formatter) -> fmt::Result {
+    let sign = if v.0 < 0 { "-" } else { "" };
+    let cents = v.0.unsigned_abs();
+    write!(f, "{}{}.{:02}", sign, cents / 100, cents % 100)
+}
+
+/// Format the array as `[a, b, c]`.
+fn fmt_array<T: fmt::Display>(v: &[T], f: &mut fmt::Formatter) -> fmt::Result {
+    let values: Vec<String> = v.iter().map(|value| value.to_string()).collect();
+    write!(f, "[{}]", values.join(", "))
+}
+
+/// Format the bytes as the hex `bytea` text, such as `\x0aff`.
+pub(crate) fn fmt_bytea(v: &[u8]) -> String {
+    let hex: String = v.iter().map(|b| format!("{:02x}", b)).collect();
+    format!("\\x{}", hex)
+}
+
+/// Format the array as the postgresql array literal, such as `{1,2}`.
+fn fmt_array_literal<T: fmt::Display>(v: &[T], quoted: bool) -> String {
+    let values: Vec<String> = v
+        .iter()
+        .map(|value| match quoted {
+            true => {
+                let value = value.to_string().replace('\\', "\\\\").replace('"', "\\\"");
+                format!("\"{}\"", value)
+            }
+            false => value.to_string(),
+        })
+        .collect();
+    format!("{{{}}}", values.join(","))
 }
 
 impl fmt::Display for PostgreSQLDataTypes {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
+            PostgreSQLDataTypes::Null => write!(f, "{}", NULL),
             PostgreSQLDataTypes::Bool(v) => write!(f, "{}", v),
             PostgreSQLDataTypes::I8(v) => write!(f, "{}", v),
             PostgreSQLDataTypes::I16(v) => write!(f, "{}", v),
@@ -75,14 +304,14 @@ impl fmt::Display for PostgreSQLDataTypes {
             PostgreSQLDataTypes::String(v) => write!(f, "{}", v),
             PostgreSQLDataTypes::Binary(_) => write!(f, "{}", BINARY),
             PostgreSQLDataTypes::Void(_) => write!(f, "()"),
-            PostgreSQLDataTypes::PgInterval(_) => write!(f, "{}", PGINTERVAL),
+            PostgreSQLDataTypes::PgInterval(v) => fmt_interval(v, f),
             PostgreSQLDataTypes::PgRangeBigDecimal(v) => write!(f, "{}", v),
             PostgreSQLDataTypes::PgRangeDateTime(v) => write!(f, "{}", v),
             PostgreSQLDataTypes::PgRangeNaiveDate(v) => write!(f, "{}", v),
             PostgreSQLDataTypes::PgRangeNaiveDateTime(v) => write!(f, "{}", v),
             PostgreSQLDataTypes::PgRangeI32(v) => write!(f, "{}", v),
             PostgreSQLDataTypes::PgRangeI64(v) => write!(f, "{}", v),
-            PostgreSQLDataTypes::PgMoney(_) => write!(f, "{}", PGMONEY),
+            PostgreSQLDataTypes::PgMoney(v) => fmt_money(v, f),
             PostgreSQLDataTypes::PgLTree(v) => write!(f, "{}", v),
             PostgreSQLDataTypes::PgLQuery(v) => write!(f, "{}", v),
             PostgreSQLDataTypes::BigDecimal(v) => write!(f, "{}", v),
@@ -90,20 +319,394 @@ impl fmt::Display for PostgreSQLDataTypes {
             PostgreSQLDataTypes::NaiveDateTime(v) => write!(f, "{}", v),
             PostgreSQLDataTypes::NaiveDate(v) => write!(f, "{}", v),
             PostgreSQLDataTypes::NaiveTime(v) => write!(f, "{}", v),
-            PostgreSQLDataTypes::PgTimeTz(_) => write!(f, "{}", PGTIMETZ),
+            PostgreSQLDataTypes::PgTimeTz(v) => write!(f, "{}{}", v.time, v.offset),
             PostgreSQLDataTypes::Uuid(v) => write!(f, "{}", v),
             PostgreSQLDataTypes::IpNetwork(v) => write!(f, "{}", v),
             PostgreSQLDataTypes::MacAddress(v) => write!(f, "{}", v),
             PostgreSQLDataTypes::BitVec(_) => write!(f, "{}", BINARY),
             PostgreSQLDataTypes::JsonValue(v) => write!(f, "{}", v),
+            PostgreSQLDataTypes::ArrayI32(v) => fmt_array(v, f),
+            PostgreSQLDataTypes::ArrayI64(v) => fmt_array(v, f),
+            PostgreSQLDataTypes::ArrayF64(v) => fmt_array(v, f),
+            PostgreSQLDataTypes::ArrayString(v) => fmt_array(v, f),
+            PostgreSQLDataTypes::ArrayBool(v) => fmt_array(v, f),
+            PostgreSQLDataTypes::PgPoint(v) => write!(f, "{}", v.to_literal()),
+            PostgreSQLDataTypes::PgLine(v) => write!(f, "{}", v.to_literal()),
+            PostgreSQLDataTypes::PgLSeg(v) => write!(f, "{}", v.to_literal()),
+            PostgreSQLDataTypes::PgBox(v) => write!(f, "{}", v.to_literal()),
+            PostgreSQLDataTypes::PgPath(v) => write!(f, "{}", v.to_literal()),
+            PostgreSQLDataTypes::PgPolygon(v) => write!(f, "{}", v.to_literal()),
+            PostgreSQLDataTypes::PgCircle(v) => write!(f, "{}", v.to_literal()),
+        }
+    }
+}
+
+impl PostgreSQLDataTypes {
+    /// Returns the postgresql type name of the value, such as `BOOL`.
+    ///
+    /// The unsigned integers which postgresql does not have are named as the signed type holding them.
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            PostgreSQLDataTypes::Null => "NULL",
+            PostgreSQLDataTypes::Bool(_) => "BOOL",
+            PostgreSQLDataTypes::I8(_) => "CHAR",
+            PostgreSQLDataTypes::I16(_) => "INT2",
+            PostgreSQLDataTypes::I32(_) => "INT4",
+            PostgreSQLDataTypes::I64(_) => "INT8",
+            PostgreSQLDataTypes::U8(_) => "INT2",
+            PostgreSQLDataTypes::U16(_) => "INT4",
+            PostgreSQLDataTypes::U64(_) => "INT8",
+            PostgreSQLDataTypes::F32(_) => "FLOAT4",
+            PostgreSQLDataTypes::F64(_) => "FLOAT8",
+            PostgreSQLDataTypes::String(_) => "TEXT",
+            PostgreSQLDataTypes::Binary(_) => "BYTEA",
+            PostgreSQLDataTypes::Void(_) => "VOID",
+            PostgreSQLDataTypes::PgInterval(_) => "INTERVAL",
+            PostgreSQLDataTypes::PgRangeBigDecimal(_) => "NUMRANGE",
+            PostgreSQLDataTypes::PgRangeDateTime(_) => "TSTZRANGE",
+            PostgreSQLDataTypes::PgRangeNaiveDate(_) => "DATERANGE",
+            PostgreSQLDataTypes::PgRangeNaiveDateTime(_) => "TSRANGE",
+            PostgreSQLDataTypes::PgRangeI32(_) => "INT4RANGE",
+            PostgreSQLDataTypes::PgRangeI64(_) => "INT8RANGE",
+            PostgreSQLDataTypes::PgMoney(_) => "MONEY",
+            PostgreSQLDataTypes::PgLTree(_) => "LTREE",
+            PostgreSQLDataTypes::PgLQuery(_) => "LQUERY",
+            PostgreSQLDataTypes::BigDecimal(_) => "NUMERIC",
+            PostgreSQLDataTypes::DateTime(_) => "TIMESTAMPTZ",
+            PostgreSQLDataTypes::NaiveDateTime(_) => "TIMESTAMP",
+            PostgreSQLDataTypes::NaiveDate(_) => "DATE",
+            PostgreSQLDataTypes::NaiveTime(_) => "TIME",
+            PostgreSQLDataTypes::PgTimeTz(_) => "TIMETZ",
+            PostgreSQLDataTypes::Uuid(_) => "UUID",
+            PostgreSQLDataTypes::IpNetwork(_) => "INET",
+            PostgreSQLDataTypes::MacAddress(_) => "MACADDR",
+            PostgreSQLDataTypes::BitVec(_) => "VARBIT",
+            PostgreSQLDataTypes::JsonValue(_) => "JSONB",
+            PostgreSQLDataTypes::ArrayI32(_) => "INT4[]",
+            PostgreSQLDataTypes::ArrayI64(_) => "INT8[]",
+            PostgreSQLDataTypes::ArrayF64(_) => "FLOAT8[]",
+            PostgreSQLDataTypes::ArrayString(_) => "TEXT[]",
+            PostgreSQLDataTypes::ArrayBool(_) => "BOOL[]",
+            PostgreSQLDataTypes::PgPoint(_) => "POINT",
+            PostgreSQLDataTypes::PgLine(_) => "LINE",
+            PostgreSQLDataTypes::PgLSeg(_) => "LSEG",
+            PostgreSQLDataTypes::PgBox(_) => "BOX",
+            PostgreSQLDataTypes::PgPath(_) => "PATH",
+            PostgreSQLDataTypes::PgPolygon(_) => "POLYGON",
+            PostgreSQLDataTypes::PgCircle(_) => "CIRCLE",
         }
     }
+    pub(crate) fn order_key(&self) -> OrderKey<'_> {
+        match self {
+            PostgreSQLDataTypes::Null => OrderKey::Null,
+            PostgreSQLDataTypes::Bool(v) => OrderKey::Bool(*v),
+            PostgreSQLDataTypes::I8(v) => OrderKey::Integer(i128::from(*v)),
+            PostgreSQLDataTypes::I16(v) => OrderKey::Integer(i128::from(*v)),
+            PostgreSQLDataTypes::I32(v) => OrderKey::Integer(i128::from(*v)),
+            PostgreSQLDataTypes::I64(v) => OrderKey::Integer(i128::from(*v)),
+            PostgreSQLDataTypes::U8(v) => OrderKey::Integer(i128::from(*v)),
+            PostgreSQLDataTypes::U16(v) => OrderKey::Integer(i128::from(*v)),
+            PostgreSQLDataTypes::U64(v) => OrderKey::Integer(i128::from(*v)),
+            PostgreSQLDataTypes::F32(v) => OrderKey::Float(f64::from(*v)),
+            PostgreSQLDataTypes::F64(v) => OrderKey::Float(*v),
+            PostgreSQLDataTypes::BigDecimal(v) => OrderKey::Decimal(v),
+            PostgreSQLDataTypes::String(v) if v == UNKNOWN => OrderKey::Other,
+            PostgreSQLDataTypes::String(v) => OrderKey::String(v),
+            PostgreSQLDataTypes::Binary(v) => OrderKey::Binary(v),
+            PostgreSQLDataTypes::DateTime(v) => OrderKey::DateTime(v.naive_utc()),
+            PostgreSQLDataTypes::NaiveDateTime(v) => OrderKey::DateTime(*v),
+            PostgreSQLDataTypes::NaiveDate(v) => OrderKey::DateTime(v.and_time(NaiveTime::MIN)),
+            PostgreSQLDataTypes::NaiveTime(v) => OrderKey::Time(*v),
+            _ => OrderKey::Other,
+        }
+    }
+    pub(crate) fn sort_key(&self) -> SortKey {
+        match self {
+            PostgreSQLDataTypes::Bool(v) => SortKey::Number(f64::from(u8::from(*v))),
+            PostgreSQLDataTypes::I8(v) => SortKey::Number(*v as f64),
+            PostgreSQLDataTypes::I16(v) => SortKey::Number(*v as f64),
+            PostgreSQLDataTypes::I32(v) => SortKey::Number(*v as f64),
+            PostgreSQLDataTypes::I64(v) => SortKey::Number(*v as f64),
+            PostgreSQLDataTypes::U8(v) => SortKey::Number(*v as f64),
+            PostgreSQLDataTypes::U16(v) => SortKey::Number(*v as f64),
+            PostgreSQLDataTypes::U64(v) => SortKey::Number(*v as f64),
+            PostgreSQLDataTypes::F32(v) => SortKey::Number(*v as f64),
+            PostgreSQLDataTypes::F64(v) => SortKey::Number(*v),
+            PostgreSQLDataTypes::BigDecimal(v) => v
+                .to_string()
+                .parse()
+                .map_or(SortKey::Other, SortKey::Number),
+            PostgreSQLDataTypes::String(v) if v != UNKNOWN => SortKey::String(v.clone()),
+            PostgreSQLDataTypes::DateTime(v) => SortKey::DateTime(v.naive_utc()),
+            PostgreSQLDataTypes::NaiveDateTime(v) => SortKey::DateTime(*v),
+            PostgreSQLDataTypes::NaiveDate(v) => SortKey::DateTime(v.and_time(NaiveTime::MIN)),
+            PostgreSQLDataTypes::NaiveTime(v) => SortKey::Time(*v),
+            _ => SortKey::Other,
+        }
+    }
+    pub(crate) fn to_json_value(&self) -> JsonValue {
+        match self {
+            PostgreSQLDataTypes::Null | PostgreSQLDataTypes::Void(_) => JsonValue::Null,
+            PostgreSQLDataTypes::Bool(v) => JsonValue::from(*v),
+            PostgreSQLDataTypes::I8(v) => JsonValue::from(*v),
+            PostgreSQLDataTypes::I16(v) => JsonValue::from(*v),
+            PostgreSQLDataTypes::I32(v) => JsonValue::from(*v),
+            PostgreSQLDataTypes::I64(v) => JsonValue::from(*v),
+            PostgreSQLDataTypes::U8(v) => JsonValue::from(*v),
+            PostgreSQLDataTypes::U16(v) => JsonValue::from(*v),
+            PostgreSQLDataTypes::U64(v) => JsonValue::from(*v),
+            PostgreSQLDataTypes::F32(v) => JsonValue::from(*v),
+            PostgreSQLDataTypes::F64(v) => JsonValue::from(*v),
+            PostgreSQLDataTypes::String(v) => JsonValue::from(v.as_str()),
+            PostgreSQLDataTypes::Binary(v) => JsonValue::from(STANDARD.encode(v)),
+            PostgreSQLDataTypes::BitVec(v) => JsonValue::from(STANDARD.encode(v.to_bytes())),
+            PostgreSQLDataTypes::JsonValue(v) => v.clone(),
+            PostgreSQLDataTypes::ArrayI32(v) => JsonValue::from(v.clone()),
+            PostgreSQLDataTypes::ArrayI64(v) => JsonValue::from(v.clone()),
+            PostgreSQLDataTypes::ArrayF64(v) => JsonValue::from(v.clone()),
+            PostgreSQLDataTypes::ArrayString(v) => JsonValue::from(v.clone()),
+            PostgreSQLDataTypes::ArrayBool(v) => JsonValue::from(v.clone()),
+            _ => JsonValue::from(self.to_string()),
+        }
+    }
+    pub(crate) fn is_null(&self) -> bool {
+        matches!(self, PostgreSQLDataTypes::Null)
+    }
+    /// Returns the elements of the array, `None` if the value is not an array.
+    pub(crate) fn array_elements(&self) -> Option<Vec<PostgreSQLDataTypes>> {
+        let elements = match self {
+            PostgreSQLDataTypes::ArrayI32(v) => {
+                v.iter().map(|e| PostgreSQLDataTypes::I32(*e)).collect()
+            }
+            PostgreSQLDataTypes::ArrayI64(v) => {
+                v.iter().map(|e| PostgreSQLDataTypes::I64(*e)).collect()
+            }
+            PostgreSQLDataTypes::ArrayF64(v) => {
+                v.iter().map(|e| PostgreSQLDataTypes::F64(*e)).collect()
+            }
+            PostgreSQLDataTypes::ArrayString(v) => v
+                .iter()
+                .map(|e| PostgreSQLDataTypes::String(e.clone()))
+                .collect(),
+            PostgreSQLDataTypes::ArrayBool(v) => {
+                v.iter().map(|e| PostgreSQLDataTypes::Bool(*e)).collect()
+            }
+            _ => return None,
+        };
+        Some(elements)
+    }
+    /// Returns the text of the value accepted by postgresql as input, `None` for the null value.
+    pub(crate) fn to_input_text(&self) -> Option<String> {
+        match self {
+            PostgreSQLDataTypes::Null => None,
+            PostgreSQLDataTypes::Binary(v) => Some(fmt_bytea(v)),
+            PostgreSQLDataTypes::BitVec(v) => {
+                Some(v.iter().map(|b| if b { '1' } else { '0' }).collect())
+            }
+            PostgreSQLDataTypes::ArrayI32(v) => Some(fmt_array_literal(v, false)),
+            PostgreSQLDataTypes::ArrayI64(v) => Some(fmt_array_literal(v, false)),
+            PostgreSQLDataTypes::ArrayF64(v) => Some(fmt_array_literal(v, false)),
+            PostgreSQLDataTypes::ArrayString(v) => Some(fmt_array_literal(v, true)),
+            PostgreSQLDataTypes::ArrayBool(v) => Some(fmt_array_literal(v, false)),
+            _ => Some(self.to_string()),
+        }
+    }
+    pub(crate) fn is_numeric_or_bool(&self) -> bool {
+        matches!(
+            self,
+            PostgreSQLDataTypes::Bool(_)
+                | PostgreSQLDataTypes::I8(_)
+                | PostgreSQLDataTypes::I16(_)
+                | PostgreSQLDataTypes::I32(_)
+                | PostgreSQLDataTypes::I64(_)
+                | PostgreSQLDataTypes::U8(_)
+                | PostgreSQLDataTypes::U16(_)
+                | PostgreSQLDataTypes::U64(_)
+                | PostgreSQLDataTypes::F32(_)
+                | PostgreSQLDataTypes::F64(_)
+                | PostgreSQLDataTypes::BigDecimal(_)
+        )
+    }
 }
 
-pub async fn rows_process(rows: Vec<PgRow>) -> anyhow::Result<SQLRets> {
-    let mut sql_rets = SQLRets::new();
+/// The output format of `PostgreSQL::execute_explain`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExplainFormat {
+    Text,
+    Json,
+    Xml,
+    Yaml,
+}
 
-    if rows.len() > 0 {
+impl ExplainFormat {
+    pub(crate) fn as_str(&self) -> &'static str {
+        match self {
+            ExplainFormat::Text => "TEXT",
+            ExplainFormat::Json => "JSON",
+            ExplainFormat::Xml => "XML",
+            ExplainFormat::Yaml => "YAML",
+        }
+    }
+}
+
+/// The `Execution Time` in milliseconds of the `EXPLAIN (ANALYZE, FORMAT JSON)` plan.
+pub fn execution_time_ms(plan: &JsonValue) -> Option<f64> {
+    plan.get(0)?.get("Execution Time")?.as_f64()
+}
+
+/// The cost estimates of the planner for the whole query, see `PostgreSQL::explain_costs`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct QueryCost {
+    /// The cost before the first row can be returned.
+    pub startup_cost: f64,
+    /// The cost to return all the rows.
+    pub total_cost: f64,
+    /// The estimated number of the rows returned.
+    pub plan_rows: u64,
+    /// The estimated average width of the rows in bytes.
+    pub plan_width: u32,
+}
+
+/// The costs of the top `Plan` node of the `EXPLAIN (FORMAT JSON)` plan, the first one found by walking the json.
+pub fn query_cost(plan: &JsonValue) -> Option<QueryCost> {
+    match plan {
+        JsonValue::Array(values) => values.iter().find_map(query_cost),
+        JsonValue::Object(object) => match object.get("Plan") {
+            Some(node) => Some(QueryCost {
+                startup_cost: node.get("Startup Cost")?.as_f64()?,
+                total_cost: node.get("Total Cost")?.as_f64()?,
+                plan_rows: node.get("Plan Rows")?.as_f64()? as u64,
+                plan_width: node.get("Plan Width")?.as_u64()?.try_into().ok()?,
+            }),
+            None => object.values().find_map(query_cost),
+        },
+        _ => None,
+    }
+}
+
+/// The options to connect to postgresql.
+#[derive(Debug, Clone)]
+pub struct PostgreSQLConnectOptions {
+    url: String,
+    connect_timeout: Option<Duration>,
+    statement_timeout: Option<Duration>,
+    tls: Option<TlsMode>,
+    client_cert: Option<(PathBuf, PathBuf)>,
+}
+
+impl PostgreSQLConnectOptions {
+    pub fn new(url: &str) -> PostgreSQLConnectOptions {
+        PostgreSQLConnectOptions {
+            url: url.to_string(),
+            connect_timeout: None,
+            statement_timeout: None,
+            tls: None,
+            client_cert: None,
+        }
+    }
+    /// Give up the connection attempt after the timeout.
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+    /// Abort the statement running longer than the timeout, it is set by `SET statement_timeout` after connecting.
+    pub fn statement_timeout(mut self, timeout: Duration) -> Self {
+        self.statement_timeout = Some(timeout);
+        self
+    }
+    /// Set the tls mode, the mode in the url (if any) is used if not set.
+    pub fn tls(mut self, tls: TlsMode) -> Self {
+        self.tls = Some(tls);
+        self
+    }
+    /// Authenticate with the client certificate and key files.
+    pub fn client_cert(mut self, cert: PathBuf, key: PathBuf) -> Self {
+        self.client_cert = Some((cert, key));
+        self
+    }
+    pub(crate) fn set_url(&mut self, url: &str) {
+        self.url = url.to_string();
+    }
+    fn pg_connect_options(&self) -> Result<PgConnectOptions> {
+        let mut options = PgConnectOptions::from_str(&self.url)?;
+        options = match &self.tls {
+            Some(TlsMode::Disabled) => options.ssl_mode(PgSslMode::Disable),
+            Some(TlsMode::Preferred) => options.ssl_mode(PgSslMode::Prefer),
+            Some(TlsMode::Required) => options.ssl_mode(PgSslMode::Require),
+            Some(TlsMode::VerifyCa(ca)) => options.ssl_mode(PgSslMode::VerifyCa).ssl_root_cert(ca),
+            Some(TlsMode::VerifyFull(ca)) => {
+                options.ssl_mode(PgSslMode::VerifyFull).ssl_root_cert(ca)
+            }
+            None => options,
+        };
+        if let Some((cert, key)) = &self.client_cert {
+            options = options.ssl_client_cert(cert).ssl_client_key(key);
+        }
+        Ok(options)
+    }
+    pub(crate) async fn connect(&self) -> Result<PgConnection> {
+        let options = self.pg_connect_options()?;
+        let connect = PgConnection::connect_with(&options);
+        let mut connection = crate::with_timeout(self.connect_timeout, connect).await?;
+        if let Some(timeout) = self.statement_timeout {
+            let sql = format!("SET statement_timeout = {}", timeout.as_millis());
+            sqlx::query(&sql).execute(&mut connection).await?;
+        }
+        Ok(connection)
+    }
+    /// Connect a dedicated connection for `LISTEN`, the statement timeout is not applied to it.
+    pub(crate) async fn listener(&self) -> Result<PgListener> {
+        let options = self.pg_connect_options()?;
+        let connect = PgPoolOptions::new()
+            .max_connections(1)
+            .max_lifetime(None)
+            .idle_timeout(None)
+            .connect_with(options);
+        let pool = crate::with_timeout(self.connect_timeout, connect).await?;
+        Ok(PgListener::connect_with(&pool).await?)
+    }
+}
+
+/// The notification received from the channel listened by `PostgreSQL::listen`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Notification {
+    pub channel: String,
+    pub payload: String,
+}
+
+/// The stream of the notifications returned by `PostgreSQL::listen`, it holds a dedicated connection.
+pub struct PgNotificationStream {
+    stream: BoxStream<'static, Result<Notification>>,
+}
+
+impl PgNotificationStream {
+    pub(crate) fn new(listener: PgListener) -> PgNotificationStream {
+        let stream = listener
+            .into_stream()
+            .map(|notification| {
+                let notification = notification?;
+                Ok(Notification {
+                    channel: notification.channel().to_string(),
+                    payload: notification.payload().to_string(),
+                })
+            })
+            .boxed();
+        PgNotificationStream { stream }
+    }
+}
+
+impl Stream for PgNotificationStream {
+    type Item = Result<Notification>;
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.stream.poll_next_unpin(cx)
+    }
+}
+
+pub async fn rows_process(rows: Vec<PgRow>) -> Result<SQLRets> {
+    let columns = rows.first().map_or(0, |row| row.len());
+    let mut sql_rets = SQLRets::with_capacity(columns, rows.len());
+
+    if !rows.is_empty() {
         // push all column
         let pg_row = &rows[0];
         let mysql_row_len = pg_row.len();
@@ -115,151 +718,395 @@ pub async fn rows_process(rows: Vec<PgRow>) -> anyhow::Result<SQLRets> {
     }
 
     for pg_row in &rows {
-        let mut sql_row: HashMap<String, SQLDataTypes> = HashMap::new();
-        let pg_row_len = pg_row.len();
-        for i in 0..pg_row_len {
-            let col = pg_row.column(i);
-            let col_name = col.name().to_string();
-            let type_info = col.type_info();
-            let postgresql_value = match type_info.name() {
-                "BOOL" => {
-                    let value: bool = pg_row.get(i);
-                    PostgreSQLDataTypes::Bool(value)
-                }
-                "CHAR" => {
-                    let value: i8 = pg_row.get(i);
-                    PostgreSQLDataTypes::I8(value)
-                }
-                "SMALLINT" | "SMALLSERIAL" | "INT2" => {
-                    let value: i16 = pg_row.get(i);
-                    PostgreSQLDataTypes::I16(value)
-                }
-                "INT" | "SERIAL" | "INT4" => {
-                    let value: i32 = pg_row.get(i);
-                    PostgreSQLDataTypes::I32(value)
-                }
-                "BIGINT" | "BIGSERIAL" | "INT8" => {
-                    let value: i64 = pg_row.get(i);
-                    PostgreSQLDataTypes::I64(value)
-                }
-                "REAL" | "FLOAT4" => {
-                    let value: f32 = pg_row.get(i);
-                    PostgreSQLDataTypes::F32(value)
-                }
-                "DOUBLE PRECISION" | "FLOAT8" => {
-                    let value: f64 = pg_row.get(i);
-                    PostgreSQLDataTypes::F64(value)
-                }
-                "VARCHAR" | "CHAR(N)" | "TEXT" | "NAME" => {
-                    let value: String = pg_row.get(i);
-                    PostgreSQLDataTypes::String(value)
-                }
-                "BYTEA" => {
-                    let value: Vec<u8> = pg_row.get(i);
-                    PostgreSQLDataTypes::Binary(value)
-                }
-                "VOID" => {
-                    let value = ();
-                    PostgreSQLDataTypes::Void(value)
-                }
-                "INTERVAL" => {
-                    let value: PgInterval = pg_row.get(i);
-                    PostgreSQLDataTypes::PgInterval(value)
-                }
-                "NUMRANGE" => {
-                    let value: PgRange<BigDecimal> = pg_row.get(i);
-                    PostgreSQLDataTypes::PgRangeBigDecimal(value)
-                }
-                "DATERANGE" => {
-                    let value: PgRange<NaiveDate> = pg_row.get(i);
-                    PostgreSQLDataTypes::PgRangeNaiveDate(value)
-                }
-                "TSTZRANGE" => {
-                    let value: PgRange<DateTime<chrono::Utc>> = pg_row.get(i);
-                    PostgreSQLDataTypes::PgRangeDateTime(value)
-                }
-                "TSRANGE" => {
-                    let value: PgRange<NaiveDateTime> = pg_row.get(i);
-                    PostgreSQLDataTypes::PgRangeNaiveDateTime(value)
-                }
-                "INT4RANGE" => {
-                    let value: PgRange<i32> = pg_row.get(i);
-                    PostgreSQLDataTypes::PgRangeI32(value)
-                }
-                "INT8RANGE" => {
-                    let value: PgRange<i64> = pg_row.get(i);
-                    PostgreSQLDataTypes::PgRangeI64(value)
-                }
-                // "INT8RANGE" | "INT4RANGE" | "TSRANGE" | "TSTZRANGE" | "DATERANGE" | "NUMRANGE" => {
-                //     let value: PgRange<i64> = pg_row.get(i);
-                //     PostgreSQLDataType::PgRange(value)
-                // }
-                "MONEY" => {
-                    let value: PgMoney = pg_row.get(i);
-                    PostgreSQLDataTypes::PgMoney(value)
-                }
-                "LTREE" => {
-                    let value: PgLTree = pg_row.get(i);
-                    PostgreSQLDataTypes::PgLTree(value)
-                }
-                "LQUERY" => {
-                    let value: PgLQuery = pg_row.get(i);
-                    PostgreSQLDataTypes::PgLQuery(value)
-                }
-                "NUMERIC" => {
-                    let value: BigDecimal = pg_row.get(i);
-                    PostgreSQLDataTypes::BigDecimal(value)
-                }
-                "TIMESTAMPTZ" => {
-                    let value: DateTime<chrono::Utc> = pg_row.get(i);
-                    PostgreSQLDataTypes::DateTime(value)
-                }
-                "TIMESTAMP" => {
-                    let value: NaiveDateTime = pg_row.get(i);
-                    PostgreSQLDataTypes::NaiveDateTime(value)
-                }
-                "DATE" => {
-                    let value: NaiveDate = pg_row.get(i);
-                    PostgreSQLDataTypes::NaiveDate(value)
-                }
-                "TIME" => {
-                    let value: NaiveTime = pg_row.get(i);
-                    PostgreSQLDataTypes::NaiveTime(value)
-                }
-                "TIMETZ" => {
-                    let value: PgTimeTz = pg_row.get(i);
-                    PostgreSQLDataTypes::PgTimeTz(value)
-                }
-                "UUID" => {
-                    let value: Uuid = pg_row.get(i);
-                    PostgreSQLDataTypes::Uuid(value)
-                }
-                "INET" | "CIDR" => {
-                    let value: IpNetwork = pg_row.get(i);
-                    PostgreSQLDataTypes::IpNetwork(value)
-                }
-                "MACADDR" => {
-                    let value: MacAddress = pg_row.get(i);
-                    PostgreSQLDataTypes::MacAddress(value)
-                }
-                "BIT" | "VARBIT" => {
-                    let value: BitVec = pg_row.get(i);
-                    PostgreSQLDataTypes::BitVec(value)
-                }
-                "JSON" | "JSONB" => {
-                    let value: JsonValue = pg_row.get(i);
-                    PostgreSQLDataTypes::JsonValue(value)
-                }
-                _ => {
-                    PostgreSQLDataTypes::String(UNKNOWN.into())
-                }
-            };
-            let sql_value = SQLDataTypes::PostgreSQLDataTypes(postgresql_value);
-            sql_row.insert(col_name, sql_value);
-        }
+        let sql_row = row_process(pg_row)?;
         sql_rets.push_rets(sql_row);
     }
     Ok(sql_rets)
 }
 
+pub fn row_process(pg_row: &PgRow) -> Result<HashMap<String, SQLDataTypes>> {
+    let pg_row_len = pg_row.len();
+    let mut sql_row: HashMap<String, SQLDataTypes> = HashMap::with_capacity(pg_row_len);
+    for i in 0..pg_row_len {
+        let col = pg_row.column(i);
+        let col_name = col.name().to_string();
+        let type_info = col.type_info();
+        let postgresql_value = match type_info.name() {
+            "BOOL" => {
+                let value: Option<bool> = pg_row.try_get(i)?;
+                value.map_or(PostgreSQLDataTypes::Null, PostgreSQLDataTypes::Bool)
+            }
+            "CHAR" => {
+                let value: Option<i8> = pg_row.try_get(i)?;
+                value.map_or(PostgreSQLDataTypes::Null, PostgreSQLDataTypes::I8)
+            }
+            "SMALLINT" | "SMALLSERIAL" | "INT2" => {
+                let value: Option<i16> = pg_row.try_get(i)?;
+                value.map_or(PostgreSQLDataTypes::Null, PostgreSQLDataTypes::I16)
+            }
+            "INT" | "SERIAL" | "INT4" => {
+                let value: Option<i32> = pg_row.try_get(i)?;
+                value.map_or(PostgreSQLDataTypes::Null, PostgreSQLDataTypes::I32)
+            }
+            "BIGINT" | "BIGSERIAL" | "INT8" => {
+                let value: Option<i64> = pg_row.try_get(i)?;
+                value.map_or(PostgreSQLDataTypes::Null, PostgreSQLDataTypes::I64)
+            }
+            "REAL" | "FLOAT4" => {
+                let value: Option<f32> = pg_row.try_get(i)?;
+                value.map_or(PostgreSQLDataTypes::Null, PostgreSQLDataTypes::F32)
+            }
+            "DOUBLE PRECISION" | "FLOAT8" => {
+                let value: Option<f64> = pg_row.try_get(i)?;
+                value.map_or(PostgreSQLDataTypes::Null, PostgreSQLDataTypes::F64)
+            }
+            "VARCHAR" | "CHAR(N)" | "TEXT" | "NAME" => {
+                let value: Option<String> = pg_row.try_get(i)?;
+                value.map_or(PostgreSQLDataTypes::Null, PostgreSQLDataTypes::String)
+            }
+            "BYTEA" => {
+                let value: Option<Vec<u8>> = pg_row.try_get(i)?;
+                value.map_or(PostgreSQLDataTypes::Null, PostgreSQLDataTypes::Binary)
+            }
+            "VOID" => {
+                let value = ();
+                PostgreSQLDataTypes::Void(value)
+            }
+            "INTERVAL" => {
+                let value: Option<PgInterval> = pg_row.try_get(i)?;
+                value.map_or(PostgreSQLDataTypes::Null, PostgreSQLDataTypes::PgInterval)
+            }
+            "NUMRANGE" => {
+                let value: Option<PgRange<BigDecimal>> = pg_row.try_get(i)?;
+                value.map_or(
+                    PostgreSQLDataTypes::Null,
+                    PostgreSQLDataTypes::PgRangeBigDecimal,
+                )
+            }
+            "DATERANGE" => {
+                let value: Option<PgRange<NaiveDate>> = pg_row.try_get(i)?;
+                value.map_or(
+                    PostgreSQLDataTypes::Null,
+                    PostgreSQLDataTypes::PgRangeNaiveDate,
+                )
+            }
+            "TSTZRANGE" => {
+                let value: Option<PgRange<DateTime<chrono::Utc>>> = pg_row.try_get(i)?;
+                value.map_or(
+                    PostgreSQLDataTypes::Null,
+                    PostgreSQLDataTypes::PgRangeDateTime,
+                )
+            }
+            "TSRANGE" => {
+                let value: Option<PgRange<NaiveDateTime>> = pg_row.try_get(i)?;
+                value.map_or(
+                    PostgreSQLDataTypes::Null,
+                    PostgreSQLDataTypes::PgRangeNaiveDateTime,
+                )
+            }
+            "INT4RANGE" => {
+                let value: Option<PgRange<i32>> = pg_row.try_get(i)?;
+                value.map_or(PostgreSQLDataTypes::Null, PostgreSQLDataTypes::PgRangeI32)
+            }
+            "INT8RANGE" => {
+                let value: Option<PgRange<i64>> = pg_row.try_get(i)?;
+                value.map_or(PostgreSQLDataTypes::Null, PostgreSQLDataTypes::PgRangeI64)
+            }
+            // "INT8RANGE" | "INT4RANGE" | "TSRANGE" | "TSTZRANGE" | "DATERANGE" | "NUMRANGE" => {
+            //     let value: PgRange<i64> = pg_row.get(i);
+            //     PostgreSQLDataType::PgRange(value)
+            // }
+            "POINT" => {
+                let value: Option<PgPoint> = pg_row.try_get(i)?;
+                value.map_or(PostgreSQLDataTypes::Null, PostgreSQLDataTypes::PgPoint)
+            }
+            "LINE" => {
+                let value: Option<PgLine> = pg_row.try_get(i)?;
+                value.map_or(PostgreSQLDataTypes::Null, PostgreSQLDataTypes::PgLine)
+            }
+            "LSEG" => {
+                let value: Option<PgLSeg> = pg_row.try_get(i)?;
+                value.map_or(PostgreSQLDataTypes::Null, PostgreSQLDataTypes::PgLSeg)
+            }
+            "BOX" => {
+                let value: Option<PgBox> = pg_row.try_get(i)?;
+                value.map_or(PostgreSQLDataTypes::Null, PostgreSQLDataTypes::PgBox)
+            }
+            "PATH" => {
+                let value: Option<PgPath> = pg_row.try_get(i)?;
+                value.map_or(PostgreSQLDataTypes::Null, PostgreSQLDataTypes::PgPath)
+            }
+            "POLYGON" => {
+                let value: Option<PgPolygon> = pg_row.try_get(i)?;
+                value.map_or(PostgreSQLDataTypes::Null, PostgreSQLDataTypes::PgPolygon)
+            }
+            "CIRCLE" => {
+                let value: Option<PgCircle> = pg_row.try_get(i)?;
+                value.map_or(PostgreSQLDataTypes::Null, PostgreSQLDataTypes::PgCircle)
+            }
+            "MONEY" => {
+                let value: Option<PgMoney> = pg_row.try_get(i)?;
+                value.map_or(PostgreSQLDataTypes::Null, PostgreSQLDataTypes::PgMoney)
+            }
+            "LTREE" => {
+                let value: Option<PgLTree> = pg_row.try_get(i)?;
+                value.map_or(PostgreSQLDataTypes::Null, PostgreSQLDataTypes::PgLTree)
+            }
+            "LQUERY" => {
+                let value: Option<PgLQuery> = pg_row.try_get(i)?;
+                value.map_or(PostgreSQLDataTypes::Null, PostgreSQLDataTypes::PgLQuery)
+            }
+            "NUMERIC" => {
+                let value: Option<BigDecimal> = pg_row.try_get(i)?;
+                value.map_or(PostgreSQLDataTypes::Null, PostgreSQLDataTypes::BigDecimal)
+            }
+            "TIMESTAMPTZ" => {
+                let value: Option<DateTime<chrono::Utc>> = pg_row.try_get(i)?;
+                value.map_or(PostgreSQLDataTypes::Null, PostgreSQLDataTypes::DateTime)
+            }
+            "TIMESTAMP" => {
+                let value: Option<NaiveDateTime> = pg_row.try_get(i)?;
+                value.map_or(
+                    PostgreSQLDataTypes::Null,
+                    PostgreSQLDataTypes::NaiveDateTime,
+                )
+            }
+            "DATE" => {
+                let value: Option<NaiveDate> = pg_row.try_get(i)?;
+                value.map_or(PostgreSQLDataTypes::Null, PostgreSQLDataTypes::NaiveDate)
+            }
+            "TIME" => {
+                let value: Option<NaiveTime> = pg_row.try_get(i)?;
+                value.map_or(PostgreSQLDataTypes::Null, PostgreSQLDataTypes::NaiveTime)
+            }
+            "TIMETZ" => {
+                let value: Option<PgTimeTz> = pg_row.try_get(i)?;
+                value.map_or(PostgreSQLDataTypes::Null, PostgreSQLDataTypes::PgTimeTz)
+            }
+            "UUID" => {
+                let value: Option<Uuid> = pg_row.try_get(i)?;
+                value.map_or(PostgreSQLDataTypes::Null, PostgreSQLDataTypes::Uuid)
+            }
+            "INET" | "CIDR" => {
+                let value: Option<IpNetwork> = pg_row.try_get(i)?;
+                value.map_or(PostgreSQLDataTypes::Null, PostgreSQLDataTypes::IpNetwork)
+            }
+            "MACADDR" => {
+                let value: Option<MacAddress> = pg_row.try_get(i)?;
+                value.map_or(PostgreSQLDataTypes::Null, PostgreSQLDataTypes::MacAddress)
+            }
+            "BIT" | "VARBIT" => {
+                let value: Option<BitVec> = pg_row.try_get(i)?;
+                value.map_or(PostgreSQLDataTypes::Null, PostgreSQLDataTypes::BitVec)
+            }
+            "JSON" | "JSONB" => {
+                let value: Option<JsonValue> = pg_row.try_get(i)?;
+                value.map_or(PostgreSQLDataTypes::Null, PostgreSQLDataTypes::JsonValue)
+            }
+            "INT4[]" => {
+                let value: Option<Vec<i32>> = pg_row.try_get(i)?;
+                value.map_or(PostgreSQLDataTypes::Null, PostgreSQLDataTypes::ArrayI32)
+            }
+            "INT8[]" => {
+                let value: Option<Vec<i64>> = pg_row.try_get(i)?;
+                value.map_or(PostgreSQLDataTypes::Null, PostgreSQLDataTypes::ArrayI64)
+            }
+            "FLOAT8[]" => {
+                let value: Option<Vec<f64>> = pg_row.try_get(i)?;
+                value.map_or(PostgreSQLDataTypes::Null, PostgreSQLDataTypes::ArrayF64)
+            }
+            "TEXT[]" | "VARCHAR[]" | "NAME[]" => {
+                let value: Option<Vec<String>> = pg_row.try_get(i)?;
+                value.map_or(PostgreSQLDataTypes::Null, PostgreSQLDataTypes::ArrayString)
+            }
+            "BOOL[]" => {
+                let value: Option<Vec<bool>> = pg_row.try_get(i)?;
+                value.map_or(PostgreSQLDataTypes::Null, PostgreSQLDataTypes::ArrayBool)
+            }
+            // The label of the user-defined enum type is sent as the text.
+            _ if matches!(type_info.kind(), PgTypeKind::Enum(_)) => {
+                let value: Option<String> = pg_row.try_get_unchecked(i)?;
+                value.map_or(PostgreSQLDataTypes::Null, PostgreSQLDataTypes::String)
+            }
+            _ => PostgreSQLDataTypes::String(UNKNOWN.into()),
+        };
+        let sql_value = SQLDataTypes::PostgreSQLDataTypes(postgresql_value);
+        sql_row.insert(col_name, sql_value);
+    }
+    Ok(sql_row)
+}
+
+pub fn bind_params(sql: &str, params: Vec<SQLParam>) -> Result<Query<'_, Postgres, PgArguments>> {
+    crate::check_params_count(crate::count_dollar_placeholders(sql), &params)?;
+    let mut query = sqlx::query(sql);
+    for param in params {
+        query = match param {
+            SQLParam::Bool(v) => query.bind(v),
+            SQLParam::I8(v) => query.bind(v),
+            SQLParam::I16(v) => query.bind(v),
+            SQLParam::I32(v) => query.bind(v),
+            SQLParam::I64(v) => query.bind(v),
+            SQLParam::F32(v) => query.bind(v),
+            SQLParam::F64(v) => query.bind(v),
+            SQLParam::String(v) => query.bind(v),
+            SQLParam::Binary(v) => query.bind(v),
+            SQLParam::DateTime(v) => query.bind(v),
+            SQLParam::NaiveDateTime(v) => query.bind(v),
+            SQLParam::NaiveDate(v) => query.bind(v),
+            SQLParam::NaiveTime(v) => query.bind(v),
+            SQLParam::BigDecimal(v) => query.bind(v),
+            SQLParam::Uuid(v) => query.bind(v),
+            SQLParam::JsonValue(v) => query.bind(v),
+            _ => {
+                return Err(RssqlError::InvalidValue(format!(
+                    "the param type {} is not supported by postgresql",
+                    param.name()
+                )))
+            }
+        };
+    }
+    Ok(query)
+}
+
+/// Bind the value of the column to the next placeholder, the null value should be written as `NULL`
+/// in the sql instead because its type can not be known.
+pub fn bind_value<'q>(
+    query: Query<'q, Postgres, PgArguments>,
+    value: &SQLDataTypes,
+) -> Result<Query<'q, Postgres, PgArguments>> {
+    let unsupported = || RssqlError::TypeConversionError {
+        from: value.type_name().to_string(),
+        to: "PostgreSQL".to_string(),
+    };
+    let value = match value {
+        SQLDataTypes::PostgreSQLDataTypes(v) => v,
+        _ => return Err(unsupported()),
+    };
+    let query = match value {
+        PostgreSQLDataTypes::Bool(v) => query.bind(*v),
+        PostgreSQLDataTypes::I8(v) => query.bind(*v),
+        PostgreSQLDataTypes::I16(v) => query.bind(*v),
+        PostgreSQLDataTypes::I32(v) => query.bind(*v),
+        PostgreSQLDataTypes::I64(v) => query.bind(*v),
+        PostgreSQLDataTypes::U8(v) => query.bind(i16::from(*v)),
+        PostgreSQLDataTypes::U16(v) => query.bind(i32::from(*v)),
+        PostgreSQLDataTypes::U64(v) => query.bind(i64::try_from(*v)?),
+        PostgreSQLDataTypes::F32(v) => query.bind(*v),
+        PostgreSQLDataTypes::F64(v) => query.bind(*v),
+        PostgreSQLDataTypes::String(v) => query.bind(v.clone()),
+        PostgreSQLDataTypes::Binary(v) => query.bind(v.clone()),
+        PostgreSQLDataTypes::PgInterval(v) => query.bind(*v),
+        PostgreSQLDataTypes::PgMoney(v) => query.bind(*v),
+        PostgreSQLDataTypes::BigDecimal(v) => query.bind(v.clone()),
+        PostgreSQLDataTypes::DateTime(v) => query.bind(*v),
+        PostgreSQLDataTypes::NaiveDateTime(v) => query.bind(*v),
+        PostgreSQLDataTypes::NaiveDate(v) => query.bind(*v),
+        PostgreSQLDataTypes::NaiveTime(v) => query.bind(*v),
+        PostgreSQLDataTypes::Uuid(v) => query.bind(*v),
+        PostgreSQLDataTypes::IpNetwork(v) => query.bind(*v),
+        PostgreSQLDataTypes::MacAddress(v) => query.bind(*v),
+        PostgreSQLDataTypes::BitVec(v) => query.bind(v.clone()),
+        PostgreSQLDataTypes::JsonValue(v) => query.bind(v.clone()),
+        PostgreSQLDataTypes::ArrayI32(v) => query.bind(v.clone()),
+        PostgreSQLDataTypes::ArrayI64(v) => query.bind(v.clone()),
+        PostgreSQLDataTypes::ArrayF64(v) => query.bind(v.clone()),
+        PostgreSQLDataTypes::ArrayString(v) => query.bind(v.clone()),
+        PostgreSQLDataTypes::ArrayBool(v) => query.bind(v.clone()),
+        _ => return Err(unsupported()),
+    };
+    Ok(query)
+}
+
+#[cfg(feature = "serde")]
+mod serde_with {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use sqlx::postgres::types::{PgInterval, PgMoney, PgRange, PgTimeTz};
+    use sqlx::types::chrono::{FixedOffset, NaiveTime};
+    use std::fmt::Display;
+    use std::ops::Bound;
+    use std::str::FromStr;
+
+    #[derive(Serialize, Deserialize)]
+    #[serde(remote = "PgInterval")]
+    pub struct PgIntervalDef {
+        months: i32,
+        days: i32,
+        microseconds: i64,
+    }
+
+    #[derive(Serialize, Deserialize)]
+    #[serde(remote = "PgMoney")]
+    pub struct PgMoneyDef(i64);
+
+    #[derive(Serialize, Deserialize)]
+    #[serde(remote = "PgRange")]
+    pub struct PgRangeDef<T> {
+        start: Bound<T>,
+        end: Bound<T>,
+    }
+
+    /// The `PgTimeTz` is (de)serialized as the time and the offset east of UTC in seconds.
+    pub mod pg_time_tz {
+        use super::*;
+
+        pub fn serialize<S: Serializer>(
+            value: &PgTimeTz,
+            serializer: S,
+        ) -> Result<S::Ok, S::Error> {
+            (value.time, value.offset.local_minus_utc()).serialize(serializer)
+        }
+
+        pub fn deserialize<'de, D: Deserializer<'de>>(
+            deserializer: D,
+        ) -> Result<PgTimeTz, D::Error> {
+            let (time, offset): (NaiveTime, i32) = Deserialize::deserialize(deserializer)?;
+            let offset = FixedOffset::east_opt(offset)
+                .ok_or_else(|| serde::de::Error::custom("the offset is out of bounds"))?;
+            Ok(PgTimeTz { time, offset })
+        }
+    }
+
+    /// The geometric types are (de)serialized as the postgresql literal.
+    pub mod geometry {
+        use super::*;
+        use crate::postgresql::PgGeometry;
+
+        pub fn serialize<T: PgGeometry, S: Serializer>(
+            value: &T,
+            serializer: S,
+        ) -> Result<S::Ok, S::Error> {
+            serializer.serialize_str(&value.to_literal())
+        }
+
+        pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+        where
+            T: FromStr,
+            T::Err: Display,
+            D: Deserializer<'de>,
+        {
+            display_fromstr::deserialize(deserializer)
+        }
+    }
 
+    /// (De)serialize the value as the string.
+    pub mod display_fromstr {
+        use super::*;
+
+        pub fn serialize<T: Display, S: Serializer>(
+            value: &T,
+            serializer: S,
+        ) -> Result<S::Ok, S::Error> {
+            serializer.collect_str(value)
+        }
+
+        pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+        where
+            T: FromStr,
+            T::Err: Display,
+            D: Deserializer<'de>,
+        {
+            let value = String::deserialize(deserializer)?;
+            value.parse().map_err(serde::de::Error::custom)
+        }
+    }
+}
@@ -1,21 +1,45 @@
-use anyhow;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use sqlx::query::Query;
+use sqlx::sqlite::{Sqlite, SqliteArguments, SqliteConnectOptions, SqliteConnection, SqliteRow};
 use sqlx::types::chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime};
-use sqlx::{Column, Row, TypeInfo};
-use sqlx::sqlite::SqliteRow;
+use sqlx::types::JsonValue;
+use sqlx::{Column, Connection, Row, TypeInfo};
 use std::collections::HashMap;
 use std::fmt;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::Duration;
 
+use crate::OrderKey;
+use crate::Result;
+use crate::RssqlError;
 use crate::SQLDataTypes;
+use crate::SQLParam;
 use crate::SQLRets;
+use crate::SortKey;
 use crate::BINARY;
+use crate::NULL;
 use crate::UNKNOWN;
 
+/// The floats are compared and hashed by the bits, see `SQLDataTypes`.
+///
+/// sqlite stores the integers as 64 bits and sqlx does not report the declared width (such as `TINYINT`),
+/// so each integer value is returned as the narrowest of `I8`, `I16`, `I32` and `I64` which holds it,
+/// and the values of one column may have different variants. Use `i64::try_from` to read them alike.
+/// The `U32` and `U64` variants are never returned by the queries, they are for the values built by the callers.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum SQLiteDataTypes {
     /// From https://docs.rs/sqlx-sqlite/0.7.0/sqlx_sqlite/types/index.html
+    Null,
     Bool(bool),
+    I8(i8),
+    I16(i16),
     I32(i32),
     I64(i64),
+    U32(u32),
+    U64(u64),
     F64(f64),
     String(String),
     Binary(Vec<u8>),
@@ -25,12 +49,29 @@ pub enum SQLiteDataTypes {
     NaiveTime(NaiveTime),
 }
 
+impl_eq_hash!(
+    SQLiteDataTypes,
+    unit: [Null],
+    plain: [
+        Bool, I8, I16, I32, I64, U32, U64, String, Binary, NaiveDateTime, DateTime, NaiveDate,
+        NaiveTime
+    ],
+    keyed: [F64 => |v| v.to_bits()]
+);
+
+impl_ord!(SQLiteDataTypes);
+
 impl fmt::Display for SQLiteDataTypes {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
+            SQLiteDataTypes::Null => write!(f, "{}", NULL),
             SQLiteDataTypes::Bool(v) => write!(f, "{}", v),
+            SQLiteDataTypes::I8(v) => write!(f, "{}", v),
+            SQLiteDataTypes::I16(v) => write!(f, "{}", v),
             SQLiteDataTypes::I32(v) => write!(f, "{}", v),
             SQLiteDataTypes::I64(v) => write!(f, "{}", v),
+            SQLiteDataTypes::U32(v) => write!(f, "{}", v),
+            SQLiteDataTypes::U64(v) => write!(f, "{}", v),
             SQLiteDataTypes::F64(v) => write!(f, "{}", v),
             SQLiteDataTypes::String(v) => write!(f, "{}", v),
             SQLiteDataTypes::Binary(_) => write!(f, "{}", BINARY),
@@ -42,10 +83,183 @@ impl fmt::Display for SQLiteDataTypes {
     }
 }
 
-pub async fn rows_process(rows: Vec<SqliteRow>) -> anyhow::Result<SQLRets> {
-    let mut sql_rets = SQLRets::new();
+impl SQLiteDataTypes {
+    /// Returns the sqlite type name of the value, such as `BOOLEAN`.
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            SQLiteDataTypes::Null => "NULL",
+            SQLiteDataTypes::Bool(_) => "BOOLEAN",
+            SQLiteDataTypes::I8(_) => "TINYINT",
+            SQLiteDataTypes::I16(_) => "SMALLINT",
+            SQLiteDataTypes::I32(_) => "INTEGER",
+            SQLiteDataTypes::I64(_) => "BIGINT",
+            SQLiteDataTypes::U32(_) => "UNSIGNED INT",
+            SQLiteDataTypes::U64(_) => "UNSIGNED BIG INT",
+            SQLiteDataTypes::F64(_) => "REAL",
+            SQLiteDataTypes::String(_) => "TEXT",
+            SQLiteDataTypes::Binary(_) => "BLOB",
+            SQLiteDataTypes::NaiveDateTime(_) => "DATETIME",
+            SQLiteDataTypes::DateTime(_) => "DATETIME",
+            SQLiteDataTypes::NaiveDate(_) => "DATE",
+            SQLiteDataTypes::NaiveTime(_) => "TIME",
+        }
+    }
+    pub(crate) fn order_key(&self) -> OrderKey<'_> {
+        match self {
+            SQLiteDataTypes::Null => OrderKey::Null,
+            SQLiteDataTypes::Bool(v) => OrderKey::Bool(*v),
+            SQLiteDataTypes::I8(v) => OrderKey::Integer(i128::from(*v)),
+            SQLiteDataTypes::I16(v) => OrderKey::Integer(i128::from(*v)),
+            SQLiteDataTypes::I32(v) => OrderKey::Integer(i128::from(*v)),
+            SQLiteDataTypes::I64(v) => OrderKey::Integer(i128::from(*v)),
+            SQLiteDataTypes::U32(v) => OrderKey::Integer(i128::from(*v)),
+            SQLiteDataTypes::U64(v) => OrderKey::Integer(i128::from(*v)),
+            SQLiteDataTypes::F64(v) => OrderKey::Float(*v),
+            SQLiteDataTypes::String(v) if v == UNKNOWN => OrderKey::Other,
+            SQLiteDataTypes::String(v) => OrderKey::String(v),
+            SQLiteDataTypes::Binary(v) => OrderKey::Binary(v),
+            SQLiteDataTypes::DateTime(v) => OrderKey::DateTime(v.naive_utc()),
+            SQLiteDataTypes::NaiveDateTime(v) => OrderKey::DateTime(*v),
+            SQLiteDataTypes::NaiveDate(v) => OrderKey::DateTime(v.and_time(NaiveTime::MIN)),
+            SQLiteDataTypes::NaiveTime(v) => OrderKey::Time(*v),
+        }
+    }
+    pub(crate) fn sort_key(&self) -> SortKey {
+        match self {
+            SQLiteDataTypes::Bool(v) => SortKey::Number(f64::from(u8::from(*v))),
+            SQLiteDataTypes::I8(v) => SortKey::Number(f64::from(*v)),
+            SQLiteDataTypes::I16(v) => SortKey::Number(f64::from(*v)),
+            SQLiteDataTypes::I32(v) => SortKey::Number(*v as f64),
+            SQLiteDataTypes::I64(v) => SortKey::Number(*v as f64),
+            SQLiteDataTypes::U32(v) => SortKey::Number(f64::from(*v)),
+            SQLiteDataTypes::U64(v) => SortKey::Number(*v as f64),
+            SQLiteDataTypes::F64(v) => SortKey::Number(*v),
+            SQLiteDataTypes::String(v) if v != UNKNOWN => SortKey::String(v.clone()),
+            SQLiteDataTypes::DateTime(v) => SortKey::DateTime(v.naive_utc()),
+            SQLiteDataTypes::NaiveDateTime(v) => SortKey::DateTime(*v),
+            SQLiteDataTypes::NaiveDate(v) => SortKey::DateTime(v.and_time(NaiveTime::MIN)),
+            SQLiteDataTypes::NaiveTime(v) => SortKey::Time(*v),
+            _ => SortKey::Other,
+        }
+    }
+    pub(crate) fn to_json_value(&self) -> JsonValue {
+        match self {
+            SQLiteDataTypes::Null => JsonValue::Null,
+            SQLiteDataTypes::Bool(v) => JsonValue::from(*v),
+            SQLiteDataTypes::I8(v) => JsonValue::from(*v),
+            SQLiteDataTypes::I16(v) => JsonValue::from(*v),
+            SQLiteDataTypes::I32(v) => JsonValue::from(*v),
+            SQLiteDataTypes::I64(v) => JsonValue::from(*v),
+            SQLiteDataTypes::U32(v) => JsonValue::from(*v),
+            SQLiteDataTypes::U64(v) => JsonValue::from(*v),
+            SQLiteDataTypes::F64(v) => JsonValue::from(*v),
+            SQLiteDataTypes::String(v) => JsonValue::from(v.as_str()),
+            SQLiteDataTypes::Binary(v) => JsonValue::from(STANDARD.encode(v)),
+            _ => JsonValue::from(self.to_string()),
+        }
+    }
+    pub(crate) fn is_null(&self) -> bool {
+        matches!(self, SQLiteDataTypes::Null)
+    }
+    pub(crate) fn is_numeric_or_bool(&self) -> bool {
+        matches!(
+            self,
+            SQLiteDataTypes::Bool(_)
+                | SQLiteDataTypes::I8(_)
+                | SQLiteDataTypes::I16(_)
+                | SQLiteDataTypes::I32(_)
+                | SQLiteDataTypes::I64(_)
+                | SQLiteDataTypes::U32(_)
+                | SQLiteDataTypes::U64(_)
+                | SQLiteDataTypes::F64(_)
+        )
+    }
+}
+
+/// The options to connect to sqlite.
+#[derive(Debug, Clone)]
+pub struct SQLiteConnectOptions {
+    url: String,
+    connect_timeout: Option<Duration>,
+    busy_timeout: Option<Duration>,
+    wal_mode: bool,
+    foreign_keys: bool,
+}
+
+impl SQLiteConnectOptions {
+    pub fn new(url: &str) -> SQLiteConnectOptions {
+        SQLiteConnectOptions {
+            url: url.to_string(),
+            connect_timeout: None,
+            busy_timeout: None,
+            wal_mode: false,
+            foreign_keys: false,
+        }
+    }
+    /// Give up the connection attempt after the timeout.
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+    /// Wait for the database lock up to the timeout, it is set by `PRAGMA busy_timeout` after connecting.
+    ///
+    /// Sqlite has no statement timeout, the busy timeout is the closest one.
+    pub fn busy_timeout(mut self, timeout: Duration) -> Self {
+        self.busy_timeout = Some(timeout);
+        self
+    }
+    /// Switch to the write-ahead log journal by `PRAGMA journal_mode = WAL` after connecting.
+    ///
+    /// The in-memory database keeps the `memory` journal mode.
+    pub fn wal_mode(mut self, wal_mode: bool) -> Self {
+        self.wal_mode = wal_mode;
+        self
+    }
+    /// Enforce the foreign key constraints by `PRAGMA foreign_keys = ON` after connecting.
+    pub fn foreign_keys(mut self, foreign_keys: bool) -> Self {
+        self.foreign_keys = foreign_keys;
+        self
+    }
+    pub(crate) fn set_url(&mut self, url: &str) {
+        self.url = url.to_string();
+    }
+    /// Returns the path of the database file, or an error for the in-memory database.
+    pub(crate) fn filename(&self) -> Result<PathBuf> {
+        let options = SqliteConnectOptions::from_str(&self.url)?;
+        let in_memory = self.url.contains(":memory:") || self.url.contains("mode=memory");
+        match in_memory {
+            true => Err(RssqlError::InvalidArgument(
+                "the in-memory database has no file".to_string(),
+            )),
+            false => Ok(options.get_filename().to_path_buf()),
+        }
+    }
+    pub(crate) async fn connect(&self) -> Result<SqliteConnection> {
+        let connect = SqliteConnection::connect(&self.url);
+        let mut connection = crate::with_timeout(self.connect_timeout, connect).await?;
+        if let Some(timeout) = self.busy_timeout {
+            let sql = format!("PRAGMA busy_timeout = {}", timeout.as_millis());
+            sqlx::query(&sql).execute(&mut connection).await?;
+        }
+        if self.wal_mode {
+            sqlx::query("PRAGMA journal_mode = WAL")
+                .execute(&mut connection)
+                .await?;
+        }
+        if self.foreign_keys {
+            sqlx::query("PRAGMA foreign_keys = ON")
+                .execute(&mut connection)
+                .await?;
+        }
+        Ok(connection)
+    }
+}
+
+pub async fn rows_process(rows: Vec<SqliteRow>) -> Result<SQLRets> {
+    let columns = rows.first().map_or(0, |row| row.len());
+    let mut sql_rets = SQLRets::with_capacity(columns, rows.len());
 
-    if rows.len() > 0 {
+    if !rows.is_empty() {
         // push all column
         let mysql_row = &rows[0];
         let mysql_row_len = mysql_row.len();
@@ -57,56 +271,108 @@ pub async fn rows_process(rows: Vec<SqliteRow>) -> anyhow::Result<SQLRets> {
     }
 
     for mysql_row in &rows {
-        let mut sql_row: HashMap<String, SQLDataTypes> = HashMap::new();
-        let sqlite_row_len = mysql_row.len();
-
-        for i in 0..sqlite_row_len {
-            let col = mysql_row.column(i);
-            let col_name = col.name().to_string();
-            let type_info = col.type_info();
-            let sqlite_value = match type_info.name() {
-                "BOOLEAN" => {
-                    let value: bool = mysql_row.get(i);
-                    SQLiteDataTypes::Bool(value)
-                }
-                "INTEGER" => {
-                    let value: i32 = mysql_row.get(i);
-                    SQLiteDataTypes::I32(value)
-                }
-                "BIGINT" | "INT8" => {
-                    let value: i64 = mysql_row.get(i);
-                    SQLiteDataTypes::I64(value)
-                }
-                "REAL" => {
-                    let value: f64 = mysql_row.get(i);
-                    SQLiteDataTypes::F64(value)
-                }
-                "TEXT" => {
-                    let value: String = mysql_row.get(i);
-                    SQLiteDataTypes::String(value)
-                }
-                "BLOB" => {
-                    let value: Vec<u8> = mysql_row.get(i);
-                    SQLiteDataTypes::Binary(value)
-                }
-                "DATETIME" => {
-                    let value: NaiveDateTime = mysql_row.get(i);
-                    SQLiteDataTypes::NaiveDateTime(value)
-                }
-                "DATE" => {
-                    let value: NaiveDate = mysql_row.get(i);
-                    SQLiteDataTypes::NaiveDate(value)
-                }
-                "TIME" => {
-                    let value: NaiveTime = mysql_row.get(i);
-                    SQLiteDataTypes::NaiveTime(value)
-                }
-                _ => SQLiteDataTypes::String(UNKNOWN.into()),
-            };
-            let sql_value = SQLDataTypes::SQLiteDataTypes(sqlite_value);
-            sql_row.insert(col_name, sql_value);
-        }
+        let sql_row = row_process(mysql_row)?;
         sql_rets.push_rets(sql_row);
     }
     Ok(sql_rets)
 }
+
+/// Returns the narrowest integer variant which holds the value, trying `i8` and `i16` before `i32` and `i64`.
+fn narrowest_integer(value: i64) -> SQLiteDataTypes {
+    if let Ok(v) = i8::try_from(value) {
+        SQLiteDataTypes::I8(v)
+    } else if let Ok(v) = i16::try_from(value) {
+        SQLiteDataTypes::I16(v)
+    } else if let Ok(v) = i32::try_from(value) {
+        SQLiteDataTypes::I32(v)
+    } else {
+        SQLiteDataTypes::I64(value)
+    }
+}
+
+pub fn row_process(mysql_row: &SqliteRow) -> Result<HashMap<String, SQLDataTypes>> {
+    let sqlite_row_len = mysql_row.len();
+    let mut sql_row: HashMap<String, SQLDataTypes> = HashMap::with_capacity(sqlite_row_len);
+
+    for i in 0..sqlite_row_len {
+        let col = mysql_row.column(i);
+        let col_name = col.name().to_string();
+        let type_info = col.type_info();
+        let sqlite_value = match type_info.name() {
+            "BOOLEAN" => {
+                let value: Option<bool> = mysql_row.try_get(i)?;
+                value.map_or(SQLiteDataTypes::Null, SQLiteDataTypes::Bool)
+            }
+            "INTEGER" | "BIGINT" | "INT8" => {
+                let value: Option<i64> = mysql_row.try_get(i)?;
+                value.map_or(SQLiteDataTypes::Null, narrowest_integer)
+            }
+            "REAL" => {
+                let value: Option<f64> = mysql_row.try_get(i)?;
+                value.map_or(SQLiteDataTypes::Null, SQLiteDataTypes::F64)
+            }
+            "TEXT" => {
+                let value: Option<String> = mysql_row.try_get(i)?;
+                value.map_or(SQLiteDataTypes::Null, SQLiteDataTypes::String)
+            }
+            "BLOB" => {
+                let value: Option<Vec<u8>> = mysql_row.try_get(i)?;
+                value.map_or(SQLiteDataTypes::Null, SQLiteDataTypes::Binary)
+            }
+            "DATETIME" => {
+                let value: Option<NaiveDateTime> = mysql_row.try_get(i)?;
+                value.map_or(SQLiteDataTypes::Null, SQLiteDataTypes::NaiveDateTime)
+            }
+            "DATE" => {
+                let value: Option<NaiveDate> = mysql_row.try_get(i)?;
+                value.map_or(SQLiteDataTypes::Null, SQLiteDataTypes::NaiveDate)
+            }
+            "TIME" => {
+                let value: Option<NaiveTime> = mysql_row.try_get(i)?;
+                value.map_or(SQLiteDataTypes::Null, SQLiteDataTypes::NaiveTime)
+            }
+            "NULL" => SQLiteDataTypes::Null,
+            _ => SQLiteDataTypes::String(UNKNOWN.into()),
+        };
+        let sql_value = SQLDataTypes::SQLiteDataTypes(sqlite_value);
+        sql_row.insert(col_name, sql_value);
+    }
+    Ok(sql_row)
+}
+
+pub fn bind_params(
+    sql: &str,
+    params: Vec<SQLParam>,
+) -> Result<Query<'_, Sqlite, SqliteArguments<'_>>> {
+    crate::check_params_count(crate::count_question_placeholders(sql), &params)?;
+    let mut query = sqlx::query(sql);
+    for param in params {
+        query = match param {
+            SQLParam::Bool(v) => query.bind(v),
+            SQLParam::I8(v) => query.bind(v),
+            SQLParam::I16(v) => query.bind(v),
+            SQLParam::I32(v) => query.bind(v),
+            SQLParam::I64(v) => query.bind(v),
+            SQLParam::U8(v) => query.bind(v),
+            SQLParam::U16(v) => query.bind(v),
+            SQLParam::U32(v) => query.bind(v),
+            SQLParam::F32(v) => query.bind(v),
+            SQLParam::F64(v) => query.bind(v),
+            SQLParam::String(v) => query.bind(v),
+            SQLParam::Binary(v) => query.bind(v),
+            SQLParam::DateTime(v) => query.bind(v),
+            SQLParam::NaiveDateTime(v) => query.bind(v),
+            SQLParam::NaiveDate(v) => query.bind(v),
+            SQLParam::NaiveTime(v) => query.bind(v),
+            SQLParam::Uuid(v) => query.bind(v),
+            SQLParam::JsonValue(v) => query.bind(v),
+            _ => {
+                return Err(RssqlError::InvalidValue(format!(
+                    "the param type {} is not supported by sqlite",
+                    param.name()
+                )))
+            }
+        };
+    }
+    Ok(query)
+}
@@ -0,0 +1,227 @@
+use crate::Row;
+use crate::NULL;
+
+/// The style of the table rendered by `TableFormatter`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TableStyle {
+    /// The box drawn with `|`, `+` and `-`, this is the style of `Display`.
+    Box,
+    /// The github flavored markdown table.
+    Markdown,
+    /// The columns aligned by spaces without any border.
+    Plain,
+    /// The tab separated values without padding.
+    Tsv,
+}
+
+/// Render the `SQLRets` as a table string, use `TableFormatter::new()` to build one.
+///
+/// ```
+/// use rssql::{TableFormatter, TableStyle};
+/// let formatter = TableFormatter::new()
+///     .style(TableStyle::Markdown)
+///     .left_align(true)
+///     .null_display("")
+///     .build();
+/// ```
+#[derive(Debug, Clone)]
+pub struct TableFormatter {
+    style: TableStyle,
+    left_align: bool,
+    null_display: String,
+}
+
+impl Default for TableFormatter {
+    fn default() -> Self {
+        TableFormatter {
+            style: TableStyle::Box,
+            left_align: false,
+            null_display: NULL.to_string(),
+        }
+    }
+}
+
+impl TableFormatter {
+    /// Start to build the formatter, the default is the centered box style with `NULL` for null values.
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new() -> TableFormatterBuilder {
+        TableFormatterBuilder {
+            formatter: TableFormatter::default(),
+        }
+    }
+    pub(crate) fn format(&self, column: &[String], rets: &[Row]) -> String {
+        if rets.is_empty() {
+            return String::from("null");
+        }
+        let header: Vec<String> = column.to_vec();
+        let rows: Vec<Vec<String>> = rets
+            .iter()
+            .map(|row| {
+                column
+                    .iter()
+                    .map(|name| match row.get(name) {
+                        Some(value) if !value.is_null() => value.to_string(),
+                        _ => self.null_display.clone(),
+                    })
+                    .collect()
+            })
+            .collect();
+        let mut width: Vec<usize> = header.iter().map(|name| name.chars().count()).collect();
+        for row in &rows {
+            for (i, value) in row.iter().enumerate() {
+                width[i] = width[i].max(value.chars().count());
+            }
+        }
+        match self.style {
+            TableStyle::Box => self.format_box(&header, &rows, &width),
+            TableStyle::Markdown => self.format_markdown(&header, &rows, &width),
+            TableStyle::Plain => self.format_plain(&header, &rows, &width),
+            TableStyle::Tsv => {
+                let mut lines = vec![header.join("\t")];
+                lines.extend(rows.iter().map(|row| row.join("\t")));
+                lines.join("\n")
+            }
+        }
+    }
+    /// Pad the value to the width, the centered value has one more space on the right if needed.
+    fn pad(&self, value: &str, width: usize) -> String {
+        let need_pad_len = width - value.chars().count();
+        let left = if self.left_align { 0 } else { need_pad_len / 2 };
+        let right = need_pad_len - left;
+        format!("{}{}{}", " ".repeat(left), value, " ".repeat(right))
+    }
+    fn format_line(&self, values: &[String], width: &[usize]) -> String {
+        let cells: Vec<String> = values
+            .iter()
+            .zip(width)
+            .map(|(value, w)| format!(" {} ", self.pad(value, *w)))
+            .collect();
+        format!("|{}|", cells.join("|"))
+    }
+    fn format_box(&self, header: &[String], rows: &[Vec<String>], width: &[usize]) -> String {
+        let hlines: Vec<String> = width.iter().map(|w| "-".repeat(w + 2)).collect();
+        let hline = format!("+{}+", hlines.join("+"));
+        let mut lines = vec![
+            hline.clone(),
+            self.format_line(header, width),
+            hline.clone(),
+        ];
+        lines.extend(rows.iter().map(|row| self.format_line(row, width)));
+        lines.push(hline);
+        lines.join("\n")
+    }
+    fn format_markdown(&self, header: &[String], rows: &[Vec<String>], width: &[usize]) -> String {
+        let separators: Vec<String> = width
+            .iter()
+            .map(|w| match self.left_align {
+                true => format!(":{}", "-".repeat(w + 1)),
+                false => format!(":{}:", "-".repeat(*w)),
+            })
+            .collect();
+        let mut lines = vec![
+            self.format_line(header, width),
+            format!("|{}|", separators.join("|")),
+        ];
+        lines.extend(rows.iter().map(|row| self.format_line(row, width)));
+        lines.join("\n")
+    }
+    fn format_plain(&self, header: &[String], rows: &[Vec<String>], width: &[usize]) -> String {
+        let format_line = |values: &[String]| {
+            let cells: Vec<String> = values
+                .iter()
+                .zip(width)
+                .map(|(value, w)| self.pad(value, *w))
+                .collect();
+            cells.join("  ").trim_end().to_string()
+        };
+        let mut lines = vec![format_line(header)];
+        lines.extend(rows.iter().map(|row| format_line(row)));
+        lines.join("\n")
+    }
+}
+
+/// The builder of `TableFormatter`.
+#[derive(Debug, Clone)]
+pub struct TableFormatterBuilder {
+    formatter: TableFormatter,
+}
+
+impl TableFormatterBuilder {
+    /// Set the table style.
+    pub fn style(mut self, style: TableStyle) -> Self {
+        self.formatter.style = style;
+        self
+    }
+    /// Align the values to the left instead of the center.
+    pub fn left_align(mut self, left_align: bool) -> Self {
+        self.formatter.left_align = left_align;
+        self
+    }
+    /// Set the string displayed for the null values.
+    pub fn null_display(mut self, null_display: &str) -> Self {
+        self.formatter.null_display = null_display.to_string();
+        self
+    }
+    pub fn build(self) -> TableFormatter {
+        self.formatter
+    }
+}
+
+/// The options of `SQLRets::to_html_table_with_options`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TableHtmlOptions {
+    /// The `id` attribute of the `<table>`.
+    pub id: Option<String>,
+    /// The `class` attribute of the `<table>`.
+    pub class: Option<String>,
+    /// Escape `<`, `>`, `&` and `"` of the column names and values, the attributes are always escaped.
+    pub escape: bool,
+}
+
+impl Default for TableHtmlOptions {
+    fn default() -> Self {
+        TableHtmlOptions {
+            id: None,
+            class: None,
+            escape: true,
+        }
+    }
+}
+
+/// Replace `&`, `<`, `>` and `"` with the html entities.
+fn escape_html(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+pub(crate) fn format_html(column: &[String], rets: &[Row], options: &TableHtmlOptions) -> String {
+    let cell = |value: &str| match options.escape {
+        true => escape_html(value),
+        false => value.to_string(),
+    };
+    let mut html = String::from("<table");
+    if let Some(id) = &options.id {
+        html.push_str(&format!(" id=\"{}\"", escape_html(id)));
+    }
+    if let Some(class) = &options.class {
+        html.push_str(&format!(" class=\"{}\"", escape_html(class)));
+    }
+    html.push_str("><thead><tr>");
+    for name in column {
+        html.push_str(&format!("<th>{}</th>", cell(name)));
+    }
+    html.push_str("</tr></thead><tbody>");
+    for row in rets {
+        html.push_str("<tr>");
+        for name in column {
+            let value = row.get(name).map_or(NULL.to_string(), |v| v.to_string());
+            html.push_str(&format!("<td>{}</td>", cell(&value)));
+        }
+        html.push_str("</tr>");
+    }
+    html.push_str("</tbody></table>");
+    html
+}
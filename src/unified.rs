@@ -0,0 +1,98 @@
+use crate::MySQL;
+use crate::PostgreSQL;
+use crate::Result;
+use crate::RssqlError;
+use crate::SQLRets;
+use crate::SQLite;
+
+/// One of the three databases, the backend is picked by the url scheme.
+///
+/// ```
+/// use rssql::UnifiedDatabase;
+/// async fn test_unified() {
+///     let mut db = UnifiedDatabase::connect("sqlite:sqlite_test.db?mode=rwc").await.unwrap();
+///     let rets = db.execute_fetch_all("SELECT * FROM info").await.unwrap();
+///     println!("{}", rets);
+///     db.close().await;
+/// }
+/// ```
+pub enum UnifiedDatabase {
+    MySQL(MySQL),
+    PostgreSQL(PostgreSQL),
+    SQLite(SQLite),
+}
+
+impl UnifiedDatabase {
+    /// Connect to the database by the url scheme, `mysql://` and `mariadb://` for mysql (mariadb),
+    /// `postgres://`, `postgresql://` and `postgre://` for postgresql, `sqlite:` for sqlite.
+    pub async fn connect(url: &str) -> Result<UnifiedDatabase> {
+        let scheme = url.split(':').next().unwrap_or_default();
+        match scheme.to_lowercase().as_str() {
+            "mysql" | "mariadb" => Ok(UnifiedDatabase::MySQL(MySQL::connect(url).await?)),
+            "postgres" | "postgresql" | "postgre" => {
+                Ok(UnifiedDatabase::PostgreSQL(PostgreSQL::connect(url).await?))
+            }
+            "sqlite" => Ok(UnifiedDatabase::SQLite(SQLite::connect(url).await?)),
+            _ => Err(RssqlError::ParseError(format!(
+                "unsupported database url scheme: {}",
+                scheme
+            ))),
+        }
+    }
+    /// Execute the sql but do not get data from database, use `rows_affected` of the returns to get the rows affected.
+    pub async fn execute(&mut self, sql: &str) -> Result<SQLRets> {
+        match self {
+            UnifiedDatabase::MySQL(m) => m.execute(sql).await,
+            UnifiedDatabase::PostgreSQL(p) => p.execute(sql).await,
+            UnifiedDatabase::SQLite(s) => s.execute(sql).await,
+        }
+    }
+    /// Execute and fetch all.
+    pub async fn execute_fetch_all(&mut self, sql: &str) -> Result<SQLRets> {
+        match self {
+            UnifiedDatabase::MySQL(m) => m.execute_fetch_all(sql).await,
+            UnifiedDatabase::PostgreSQL(p) => p.execute_fetch_all(sql).await,
+            UnifiedDatabase::SQLite(s) => s.execute_fetch_all(sql).await,
+        }
+    }
+    /// Execute and fetch one.
+    pub async fn execute_fetch_one(&mut self, sql: &str) -> Result<SQLRets> {
+        match self {
+            UnifiedDatabase::MySQL(m) => m.execute_fetch_one(sql).await,
+            UnifiedDatabase::PostgreSQL(p) => p.execute_fetch_one(sql).await,
+            UnifiedDatabase::SQLite(s) => s.execute_fetch_one(sql).await,
+        }
+    }
+    /// Execute the `;` separated sql script statement by statement, returns the rows affected of each statement.
+    pub async fn execute_batch(&mut self, sql: &str) -> Result<Vec<u64>> {
+        match self {
+            UnifiedDatabase::MySQL(m) => m.execute_batch(sql).await,
+            UnifiedDatabase::PostgreSQL(p) => p.execute_batch(sql).await,
+            UnifiedDatabase::SQLite(s) => s.execute_batch(sql).await,
+        }
+    }
+    /// Execute the `;` separated sql script in a transaction, all the statements are rolled back if any of them failed.
+    pub async fn execute_batch_transactional(&mut self, sql: &str) -> Result<Vec<u64>> {
+        match self {
+            UnifiedDatabase::MySQL(m) => m.execute_batch_transactional(sql).await,
+            UnifiedDatabase::PostgreSQL(p) => p.execute_batch_transactional(sql).await,
+            UnifiedDatabase::SQLite(s) => s.execute_batch_transactional(sql).await,
+        }
+    }
+    /// Check if the connection is valid.
+    pub async fn check_connection(&mut self) -> Result<bool> {
+        match self {
+            UnifiedDatabase::MySQL(m) => m.check_connection().await,
+            UnifiedDatabase::PostgreSQL(p) => p.check_connection().await,
+            UnifiedDatabase::SQLite(s) => s.check_connection().await,
+        }
+    }
+    /// Close the connection.
+    pub async fn close(self) {
+        match self {
+            UnifiedDatabase::MySQL(m) => m.close().await,
+            UnifiedDatabase::PostgreSQL(p) => p.close().await,
+            UnifiedDatabase::SQLite(s) => s.close().await,
+        }
+    }
+}